@@ -0,0 +1,63 @@
+//! Named points that marks are attached to.
+
+use druid::Data;
+use norad::glyph::Identifier;
+
+use crate::design_space::{DPoint, DVec2};
+use crate::path::EntityId;
+
+/// A named point that is not part of any contour, such as the 'top' anchor
+/// that an accent is positioned against.
+#[derive(Debug, Clone, Data)]
+pub struct Anchor {
+    pub id: EntityId,
+    pub name: String,
+    pub point: DPoint,
+    /// The anchor's color and identifier, which we don't edit, but keep so
+    /// that they are saved.
+    #[data(ignore)]
+    color: Option<norad::Color>,
+    #[data(ignore)]
+    identifier: Option<Identifier>,
+}
+
+impl Anchor {
+    pub fn new(name: String, point: DPoint) -> Self {
+        Anchor {
+            id: EntityId::new_for_anchor(),
+            name,
+            point,
+            color: None,
+            identifier: None,
+        }
+    }
+
+    pub fn from_norad(src: &norad::glyph::Anchor) -> Self {
+        let point = DPoint::from_raw((src.x as f64, src.y as f64));
+        let name = src.name.clone().unwrap_or_default();
+        Anchor {
+            color: src.color.clone(),
+            identifier: src.identifier.clone(),
+            ..Anchor::new(name, point)
+        }
+    }
+
+    pub fn to_norad(&self) -> norad::glyph::Anchor {
+        let name = if self.name.is_empty() {
+            None
+        } else {
+            Some(self.name.clone())
+        };
+        norad::glyph::Anchor {
+            x: self.point.x as f32,
+            y: self.point.y as f32,
+            name,
+            color: self.color.clone(),
+            identifier: self.identifier.clone(),
+        }
+    }
+
+    pub(crate) fn nudge(&mut self, delta: DVec2) {
+        self.point = self.point + delta;
+    }
+}
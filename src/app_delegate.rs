@@ -14,7 +14,7 @@ use norad::{GlyphName, Ufo};
 use crate::consts;
 use crate::data::{AppState, Workspace};
 use crate::edit_session::EditSession;
-use crate::widgets::{Editor, EditorController, RootWindowController, ScrollZoom};
+use crate::widgets::{Editor, EditorController, ModalHost, RootWindowController, ScrollZoom};
 
 pub const EDIT_GLYPH: Selector<GlyphName> = Selector::new("runebender.open-editor-with-glyph");
 
@@ -31,10 +31,20 @@ impl AppDelegate<AppState> for Delegate {
         _env: &Env,
     ) -> Handled {
         if let Some(info) = cmd.get(druid::commands::OPEN_FILE) {
-            match Ufo::load(info.path()) {
-                Ok(ufo) => data.workspace.set_file(ufo, info.path().to_owned()),
-                Err(e) => log::error!("failed to open file {:?}: '{:?}'", info.path(), e),
-            };
+            if info.path().extension().map(|ext| ext == "designspace") == Some(true) {
+                if let Err(e) = data.workspace.open_designspace(info.path()) {
+                    log::error!("failed to open designspace {:?}: '{}'", info.path(), e);
+                }
+            } else {
+                match Ufo::load(info.path()) {
+                    Ok(ufo) => data.workspace.set_file(ufo, info.path().to_owned()),
+                    Err(e) => log::error!("failed to open file {:?}: '{:?}'", info.path(), e),
+                };
+            }
+            ctx.submit_command(consts::cmd::REBUILD_MENUS);
+            Handled::Yes
+        } else if let Some(idx) = cmd.get(consts::cmd::SWITCH_MASTER) {
+            data.workspace.set_active_master(*idx);
             ctx.submit_command(consts::cmd::REBUILD_MENUS);
             Handled::Yes
         } else if let Some(info) = cmd.get(druid::commands::SAVE_FILE) {
@@ -59,28 +69,30 @@ impl AppDelegate<AppState> for Delegate {
             data.workspace.rename_glyph(old.clone(), new.clone());
             Handled::Yes
         } else if let Some(payload) = cmd.get(EDIT_GLYPH) {
-            match data.workspace.open_glyphs.get(payload).to_owned() {
-                Some(id) => {
-                    ctx.submit_command(druid::commands::SHOW_WINDOW.to(*id));
-                }
-                None => {
-                    let session = data.workspace.get_or_create_session(&payload);
-                    let session_id = session.id;
-                    let new_win = WindowDesc::new(move || make_editor(&session))
-                        .title(move |d: &AppState, _: &_| {
-                            d.workspace
-                                .sessions
-                                .get(&session_id)
-                                .map(|s| s.name.to_string())
-                                .unwrap_or_else(|| "Unknown".to_string())
-                        })
-                        .window_size(Size::new(900.0, 800.0))
-                        .menu(crate::menus::make_menu(&data));
-
-                    let id = new_win.id;
-                    ctx.new_window(new_win);
-
-                    Arc::make_mut(&mut data.workspace.open_glyphs).insert(payload.clone(), id);
+            open_editor(ctx, data, payload);
+            Handled::Yes
+        } else if let Some(args) = cmd.get(consts::cmd::USE_AS_COMPONENT) {
+            if args.base == args.target || data.workspace.uses_component(&args.base, &args.target) {
+                log::warn!("'{}' can't be a component of '{}'", args.base, args.target);
+                return Handled::Yes;
+            }
+            let id = open_editor(ctx, data, &args.target);
+            ctx.submit_command(consts::cmd::INSERT_COMPONENT.with(args.base.clone()).to(id));
+            Handled::Yes
+        } else if let Some(glyphs) = cmd.get(consts::cmd::SET_MASTER_GLYPHS) {
+            data.workspace.set_master_glyphs(glyphs);
+            Handled::Yes
+        } else if let Some(payload) = cmd.get(consts::cmd::FIX_METRICS_KEYS) {
+            let id = open_editor(ctx, data, payload);
+            ctx.submit_command(consts::cmd::RESYNC_METRICS_KEYS.to(id));
+            Handled::Yes
+        } else if let Some(names) = cmd.get(consts::cmd::FIX_ALL_METRICS_KEYS) {
+            for name in names {
+                match data.workspace.open_glyphs.get(name) {
+                    Some(id) => ctx.submit_command(consts::cmd::RESYNC_METRICS_KEYS.to(*id)),
+                    None => {
+                        data.workspace.resync_metrics_keys(name);
+                    }
                 }
             }
             Handled::Yes
@@ -114,8 +126,36 @@ impl AppDelegate<AppState> for Delegate {
     }
 }
 
+/// Show the editor window for this glyph, opening one if needed.
+fn open_editor(ctx: &mut DelegateCtx, data: &mut AppState, name: &GlyphName) -> WindowId {
+    if let Some(id) = data.workspace.open_glyphs.get(name) {
+        ctx.submit_command(druid::commands::SHOW_WINDOW.to(*id));
+        return *id;
+    }
+
+    let session = data.workspace.get_or_create_session(name);
+    let session_id = session.id;
+    let new_win = WindowDesc::new(move || make_editor(&session))
+        .title(move |d: &AppState, _: &_| {
+            d.workspace
+                .sessions
+                .get(&session_id)
+                .map(|s| s.name.to_string())
+                .unwrap_or_else(|| "Unknown".to_string())
+        })
+        .window_size(Size::new(900.0, 800.0))
+        .menu(crate::menus::make_menu(data));
+
+    let id = new_win.id;
+    ctx.new_window(new_win);
+
+    Arc::make_mut(&mut data.workspace.open_glyphs).insert(name.clone(), id);
+    id
+}
+
 fn make_editor(session: &Arc<EditSession>) -> impl Widget<AppState> {
-    EditorController::new(ScrollZoom::new(Editor::new(session.clone())))
+    let editor = EditorController::new(ScrollZoom::new(Editor::new(session.clone())));
+    ModalHost::new(editor)
         .lens(AppState::workspace.then(Workspace::editor_state(session.id)))
         .controller(RootWindowController::default())
 }
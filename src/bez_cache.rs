@@ -167,6 +167,7 @@ impl BezCache {
             .iter()
             .flat_map(|o| o.components.iter())
         {
+            self.components.add(&comp.base, name);
             match self.rebuild_without_inval(&comp.base, glyph_getter) {
                 Some(component) => {
                     let affine: Affine = comp.transform.clone().into();
@@ -191,6 +192,23 @@ impl BezCache {
 }
 
 impl ComponentMap {
+    /// Record that `glyph` uses `base` as a component, such as when a
+    /// component is added while editing.
+    fn add(&mut self, base: &GlyphName, glyph: &GlyphName) {
+        if self
+            .inner
+            .get(base)
+            .map(|users| users.contains(glyph))
+            .unwrap_or(false)
+        {
+            return;
+        }
+        Arc::make_mut(&mut self.inner)
+            .entry(base.clone())
+            .or_default()
+            .push(glyph.clone());
+    }
+
     fn new(ufo: &Ufo) -> Self {
         let mut lookup: HashMap<GlyphName, Vec<GlyphName>> = HashMap::new();
         for name in ufo.iter_names() {
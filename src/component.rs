@@ -1,6 +1,8 @@
 //! A glyph embedded in another glyph.
 
-use druid::kurbo::Affine;
+use std::sync::Arc;
+
+use druid::kurbo::{Affine, BezPath, Point, Rect, Shape};
 use druid::Data;
 use norad::GlyphName;
 
@@ -13,6 +15,10 @@ pub struct Component {
     #[data(same_fn = "affine_eq")]
     pub transform: Affine,
     pub id: EntityId,
+    /// The outline of the base glyph, before the transform; the editor keeps
+    /// this in sync with the workspace, so that the component can be hit
+    /// tested without it.
+    pub outline: Option<Arc<BezPath>>,
 }
 
 fn affine_eq(left: &Affine, right: &Affine) -> bool {
@@ -23,11 +29,21 @@ impl Component {
     pub fn from_norad(src: &norad::glyph::Component) -> Self {
         let base = src.base.clone();
         let transform = src.transform.into();
-        let id = EntityId::new_with_parent(0);
+        let id = EntityId::new_for_component();
         Component {
             base,
             transform,
             id,
+            outline: None,
+        }
+    }
+
+    pub fn new(base: GlyphName, transform: Affine) -> Self {
+        Component {
+            base,
+            transform,
+            id: EntityId::new_for_component(),
+            outline: None,
         }
     }
 
@@ -42,6 +58,24 @@ impl Component {
         }
     }
 
+    /// The design-space bounding box of the transformed outline, if the
+    /// outline is known.
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let outline = self.outline.as_ref()?;
+        Some((self.transform * (**outline).clone()).bounding_box())
+    }
+
+    /// Returns `true` if this design-space point is inside the transformed
+    /// outline.
+    pub fn contains(&self, point: Point) -> bool {
+        match &self.outline {
+            Some(outline) if self.transform.determinant() != 0.0 => {
+                outline.contains(self.transform.inverse() * point)
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn nudge(&mut self, delta: DVec2) {
         let [a, b, c, d, t_x, t_y] = self.transform.as_coeffs();
         self.transform = Affine::new([a, b, c, d, t_x + delta.x, t_y + delta.y]);
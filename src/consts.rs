@@ -8,29 +8,68 @@ pub const GLYPHS_APP_PASTEBOARD_TYPE: FormatId = "Glyphs elements pasteboard typ
 
 /// Commands and Selectors
 pub mod cmd {
+    use std::sync::Arc;
+
     use druid::kurbo::{Point, Vec2};
     use druid::Selector;
-    use norad::GlyphName;
+    use norad::{Glyph, GlyphName};
 
+    use crate::data::Metric;
     use crate::design_space::{DPoint, DVec2};
+    use crate::edit_session::{AlignKind, Axis};
+    use crate::export::PngOptions;
+    use crate::guides::GuideSpec;
     use crate::path::EntityId;
     use crate::tools::ToolId;
 
     /// Sent when windows should rebuild their menus.
     pub const REBUILD_MENUS: Selector = Selector::new("runebender.rebuid-menus");
 
+    /// sent by the items in the 'Masters' menu
+    ///
+    /// The argument is the index of the master to edit.
+    pub const SWITCH_MASTER: Selector<usize> = Selector::new("runebender.switch-master");
+
+    /// sent by the 'apply last edit to all masters' menu item in Glyph menu
+    pub const APPLY_TO_ALL_MASTERS: Selector = Selector::new("runebender.apply-to-all-masters");
+
+    /// Sent to the root to put glyphs in the other masters, such as when an
+    /// edit is repeated in them, or undone.
+    ///
+    /// The argument is each glyph, with the index of its master.
+    pub const SET_MASTER_GLYPHS: Selector<Vec<(usize, Arc<Glyph>)>> =
+        Selector::new("runebender.set-master-glyphs");
+
     /// sent by the 'delete' menu item
     pub const DELETE: Selector = Selector::new("runebender.delete");
 
+    /// sent by the 'paste in place' menu item
+    pub const PASTE_IN_PLACE: Selector = Selector::new("runebender.paste-in-place");
+
+    /// sent by the 'paste at cursor' menu item
+    pub const PASTE_AT_CURSOR: Selector = Selector::new("runebender.paste-at-cursor");
+
     /// sent by the 'select' menu item
     pub const SELECT_ALL: Selector = Selector::new("runebender.select-all");
 
     /// sent by the 'deselect' menu item
     pub const DESELECT_ALL: Selector = Selector::new("runebender.deselect-all");
 
+    /// sent by the 'select all on-curve points' menu item
+    pub const SELECT_ON_CURVE: Selector = Selector::new("runebender.select-on-curve");
+
+    /// sent by the 'select all handles' menu item
+    pub const SELECT_OFF_CURVE: Selector = Selector::new("runebender.select-off-curve");
+
     /// sent by the 'new glyph' menu item
     pub const NEW_GLYPH: Selector = Selector::new("runebender.new-glyph");
 
+    /// sent by the 'select next contour' menu item
+    pub const SELECT_NEXT_CONTOUR: Selector = Selector::new("runebender.select-next-contour");
+
+    /// sent by the 'select previous contour' menu item
+    pub const SELECT_PREV_CONTOUR: Selector = Selector::new("runebender.select-prev-contour");
+
     /// sent by the 'delete glyph' menu item
     pub const DELETE_SELECTED_GLYPH: Selector = Selector::new("runebender.delete-selected-glyph");
 
@@ -48,12 +87,79 @@ pub mod cmd {
     /// sent by the 'add component' menu item
     pub const ADD_COMPONENT: Selector = Selector::new("runebender.add-component");
 
+    /// sent by the 'use as component in' menu item, to show a prompt for
+    /// the glyph the current glyph should be added to.
+    pub const SHOW_USE_AS_COMPONENT_PROMPT: Selector =
+        Selector::new("runebender.show-use-as-component-prompt");
+
+    /// Sent to the root to add the glyph `base` as a component of `target`.
+    ///
+    /// The target glyph is opened in an editor, where the change can be undone.
+    pub const USE_AS_COMPONENT: Selector<UseAsComponentArgs> =
+        Selector::new("runebender.use-as-component");
+
+    /// Arguments passed with the USE_AS_COMPONENT command.
+    pub struct UseAsComponentArgs {
+        pub base: GlyphName,
+        pub target: GlyphName,
+    }
+
+    /// Sent to an editor window to add a component referencing this glyph.
+    pub const INSERT_COMPONENT: Selector<GlyphName> = Selector::new("runebender.insert-component");
+
     /// sent by 'align selection' menu item in Paths menu
     pub const ALIGN_SELECTION: Selector = Selector::new("runebender.align-selection");
 
+    /// sent by the items in the 'align' submenu of the Paths menu
+    pub const ALIGN_SELECTION_TO: Selector<AlignKind> =
+        Selector::new("runebender.align-selection-to");
+
+    /// sent by the 'distribute' items in the 'align' submenu of the Paths menu
+    pub const DISTRIBUTE_SELECTION: Selector<Axis> =
+        Selector::new("runebender.distribute-selection");
+
     // sent by 'reverse contours' menu item in Paths menu
     pub const REVERSE_CONTOURS: Selector = Selector::new("runebender.reverse-contours");
 
+    /// sent by 'correct path direction' menu item in Paths menu
+    pub const CORRECT_PATH_DIRECTION: Selector = Selector::new("runebender.correct-path-direction");
+
+    /// sent by 'toggle tangent point' menu item in Paths menu
+    pub const TOGGLE_TANGENT: Selector = Selector::new("runebender.toggle-tangent");
+
+    /// sent by 'smooth selection' menu item in Paths menu
+    pub const SMOOTH_SELECTION: Selector = Selector::new("runebender.smooth-selection");
+
+    /// sent by 'remove stray off-curve points' menu item in Paths menu
+    pub const REMOVE_STRAY_OFFCURVES: Selector = Selector::new("runebender.remove-stray-offcurves");
+
+    /// sent by 'close open contours' menu item in Paths menu
+    pub const CLOSE_ALL_CONTOURS: Selector = Selector::new("runebender.close-all-contours");
+
+    /// sent by 'make curvature continuous' menu item in Paths menu
+    pub const MAKE_G2_CONTINUOUS: Selector = Selector::new("runebender.make-g2-continuous");
+
+    /// sent by the 'inset outline' and 'outset outline' menu items in Paths menu
+    ///
+    /// The argument is the distance to offset by, negative to inset.
+    pub const OFFSET_OUTLINE: Selector<f64> = Selector::new("runebender.offset-outline");
+
+    /// Sent to an editor window to make its glyph's sidebearings match their
+    /// metrics keys.
+    pub const RESYNC_METRICS_KEYS: Selector = Selector::new("runebender.resync-metrics-keys");
+
+    /// Sent from the metrics key checker to resync a single glyph.
+    ///
+    /// This opens the glyph in an editor, so the change can be undone.
+    pub const FIX_METRICS_KEYS: Selector<GlyphName> = Selector::new("runebender.fix-metrics-keys");
+
+    /// Sent from the metrics key checker to resync a list of glyphs.
+    ///
+    /// Glyphs that are open in an editor are resynced there; the others are
+    /// changed in the font directly.
+    pub const FIX_ALL_METRICS_KEYS: Selector<Vec<GlyphName>> =
+        Selector::new("runebender.fix-all-metrics-keys");
+
     /// Sent when a new tool has been selected.
     ///
     /// The payload must be a `ToolId`.
@@ -67,6 +173,10 @@ pub mod cmd {
     /// or a keyup (false).
     pub const TOGGLE_PREVIEW_TOOL: Selector<bool> = Selector::new("runebender.tool-preview-toggle");
 
+    /// Sent by the editor to scroll the canvas by this many screen units, when
+    /// panning with the mouse.
+    pub const PAN_CANVAS: Selector<Vec2> = Selector::new("runebender.pan-canvas");
+
     /// Sent when the 'zoom in' menu item is selected
     pub const ZOOM_IN: Selector = Selector::new("runebender.zoom-in");
 
@@ -76,6 +186,23 @@ pub mod cmd {
     /// Sent when the 'reset zoom' menu item is selected
     pub const ZOOM_DEFAULT: Selector = Selector::new("runebender.zoom-default");
 
+    /// Sent when the 'pixel preview' menu item is selected, to show or hide
+    /// the pixel preview panel.
+    pub const TOGGLE_PIXEL_PREVIEW: Selector = Selector::new("runebender.toggle-pixel-preview");
+
+    /// Sent when the 'show changes since save' menu item is selected.
+    pub const TOGGLE_SAVED_DIFF: Selector = Selector::new("runebender.toggle-saved-diff");
+
+    /// Sent when the 'show handles' menu item is selected.
+    pub const TOGGLE_HANDLES: Selector = Selector::new("runebender.toggle-handles");
+
+    /// Sent when the 'snap to grid' menu item is selected.
+    pub const TOGGLE_GRID_SNAP: Selector = Selector::new("runebender.toggle-grid-snap");
+
+    /// Sent when the 'highlight overlaps' menu item is selected.
+    pub const TOGGLE_OVERLAP_HIGHLIGHT: Selector =
+        Selector::new("runebender.toggle-overlap-highlight");
+
     /// Sent when the 'add guide' context menu item is selected
     ///
     /// The arguments **must** be a `Point`, where the guide will be added.
@@ -86,12 +213,44 @@ pub mod cmd {
     /// The arguments **must** be a `ToggleGuideCmdArgs`.
     pub const TOGGLE_GUIDE: Selector<ToggleGuideCmdArgs> = Selector::new("runebender.toggle-guide");
 
+    /// Sent when the 'add guide at' context menu item is selected, to show
+    /// a prompt for the guide's position.
+    pub const SHOW_GUIDE_PROMPT: Selector = Selector::new("runebender.show-guide-prompt");
+
+    /// Sent by the guide prompt to add a guide at an exact position.
+    ///
+    /// The arguments **must** be a `GuideSpec`.
+    pub const ADD_GUIDE_AT: Selector<GuideSpec> = Selector::new("runebender.add-guide-at");
+
     /// Arguments passed along with the TOGGLE_GUIDE command
     pub struct ToggleGuideCmdArgs {
         pub id: EntityId,
         pub pos: Point,
     }
 
+    /// Sent by the 'add anchor' and 'rename anchor' context menu items, to
+    /// show a prompt for the anchor's name.
+    pub const SHOW_ANCHOR_PROMPT: Selector<AnchorTarget> =
+        Selector::new("runebender.show-anchor-prompt");
+
+    /// Sent by the anchor prompt to add or rename an anchor.
+    pub const SET_ANCHOR_NAME: Selector<SetAnchorNameArgs> =
+        Selector::new("runebender.set-anchor-name");
+
+    /// The anchor named by the anchor prompt.
+    #[derive(Debug, Clone, Copy)]
+    pub enum AnchorTarget {
+        /// A new anchor, at this position on the screen.
+        New(Point),
+        Existing(EntityId),
+    }
+
+    /// Arguments passed along with the SET_ANCHOR_NAME command
+    pub struct SetAnchorNameArgs {
+        pub target: AnchorTarget,
+        pub name: String,
+    }
+
     /// A hack: asks the editor view to take focus, so that it can handle
     /// keyboard events.
     ///
@@ -114,6 +273,32 @@ pub mod cmd {
         pub is_left: bool,
     }
 
+    /// Sent by the 'export PNG' menu item, to show the export options panel.
+    pub const SHOW_EXPORT_PNG_PROMPT: Selector = Selector::new("runebender.show-export-png");
+
+    /// Sent from the export panel, to write the current glyph to a PNG file.
+    pub const EXPORT_PNG: Selector<PngOptions> = Selector::new("runebender.export-png");
+
+    /// Sent by the 'glyph lib' menu item, to show the glyph lib panel.
+    pub const SHOW_GLYPH_LIB: Selector = Selector::new("runebender.show-glyph-lib");
+
+    /// Sent from the glyph lib panel when a value is edited.
+    pub const SET_LIB_VALUE: Selector<SetLibValueArgs> = Selector::new("runebender.set-lib-value");
+
+    pub struct SetLibValueArgs {
+        pub key: String,
+        pub value: plist::Value,
+    }
+
+    /// Sent from the coord panel when the overshoot of the selection is edited.
+    pub const SET_SELECTION_OVERSHOOT: Selector<SetOvershootArgs> =
+        Selector::new("runebender.editor-set-selection-overshoot");
+
+    pub struct SetOvershootArgs {
+        pub metric: Metric,
+        pub value: f64,
+    }
+
     /// Sent from the coord panel when the selection bbox is manually edited.
     pub const SCALE_SELECTION: Selector<ScaleSelectionArgs> =
         Selector::new("runebender.editor-scale-selection");
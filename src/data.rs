@@ -6,17 +6,22 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use druid::kurbo::{BezPath, Point, Rect, Shape, Size};
+use druid::kurbo::{BezPath, Point, Rect, Shape, Size, Vec2};
 use druid::{Data, Lens, WindowId};
 use norad::glyph::{Contour, ContourPoint, Glyph, GlyphName, PointType};
 use norad::{FontInfo, Ufo};
 
 use crate::bez_cache::BezCache;
+use crate::designspace::DesignSpace;
 use crate::edit_session::{EditSession, SessionId};
+use crate::metrics_keys::{self, Side};
 
 /// This is by convention.
 const DEFAULT_UNITS_PER_EM: f64 = 1000.;
 
+/// Components nested deeper than this are assumed to be part of a cycle.
+const MAX_COMPONENT_DEPTH: usize = 32;
+
 /// The top level data structure.
 ///
 /// Currently this just wraps `Workspace`; in the future multiple workspaces
@@ -27,6 +32,9 @@ pub struct AppState {
 }
 
 /// A workspace is a single font, corresponding to a UFO file on disk.
+///
+/// If a designspace is open, the workspace also holds its other masters;
+/// `font` is the one being edited.
 #[derive(Clone, Lens, Data, Default)]
 pub struct Workspace {
     pub font: Arc<FontObject>,
@@ -40,6 +48,23 @@ pub struct Workspace {
     // really just a store of the fully resolved Beziers of all glyphs.
     cache: Arc<BezCache>,
     pub info: SimpleFontInfo,
+    /// The masters of the open designspace, if any.
+    pub masters: Arc<Vec<Master>>,
+    active_master: usize,
+}
+
+/// One master of a designspace.
+#[derive(Clone)]
+pub struct Master {
+    pub name: String,
+    /// The master's font. This is stale while the master is active, as the
+    /// workspace then holds the current version.
+    font: Arc<FontObject>,
+    /// Whether this master has been edited, and so needs saving.
+    ///
+    /// We don't track individual edits, so this is set once the master has
+    /// been active.
+    edited: bool,
 }
 
 #[derive(Clone, Data)]
@@ -47,6 +72,11 @@ pub struct FontObject {
     pub path: Option<Arc<Path>>,
     #[data(ignore)]
     pub ufo: Ufo,
+    /// Each glyph as it was when the font was last loaded or saved. Open
+    /// sessions are written to `ufo` before then, such as when switching
+    /// masters, so it isn't always the saved version.
+    #[data(ignore)]
+    saved: Arc<HashMap<GlyphName, Arc<Glyph>>>,
     placeholder: Arc<BezPath>,
 }
 
@@ -83,7 +113,7 @@ pub struct SimpleFontInfo {
 }
 
 /// Things in `FontInfo` that are relevant while editing or drawing.
-#[derive(Clone, Data, Lens)]
+#[derive(Debug, Clone, Data, Lens)]
 pub struct FontMetrics {
     pub units_per_em: f64,
     pub descender: Option<f64>,
@@ -93,6 +123,16 @@ pub struct FontMetrics {
     pub italic_angle: Option<f64>,
 }
 
+/// One of the horizontal lines in `FontMetrics`.
+#[derive(Debug, Clone, Copy, Data, PartialEq)]
+pub enum Metric {
+    Baseline,
+    XHeight,
+    CapHeight,
+    Ascender,
+    Descender,
+}
+
 /// The state for an editor view.
 #[derive(Clone, Data, Lens)]
 pub struct EditorState {
@@ -124,16 +164,148 @@ impl Workspace {
     pub(crate) const selected_glyph: lenses::SelectedGlyph = lenses::SelectedGlyph;
 
     pub fn set_file(&mut self, ufo: Ufo, path: impl Into<Option<PathBuf>>) {
-        let obj = FontObject {
-            path: path.into().map(Into::into),
-            ufo,
-            placeholder: Arc::new(placeholder_outline()),
-        };
-        self.font = obj.into();
+        self.font = FontObject::new(ufo, path.into()).into();
+        self.info = SimpleFontInfo::from_font(&self.font);
+        self.masters = Arc::new(Vec::new());
+        self.active_master = 0;
+        self.build_path_cache();
+    }
+
+    /// Open a designspace and the UFOs of its sources, and start editing its
+    /// default master.
+    pub fn open_designspace(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let designspace = DesignSpace::load(path)?;
+        let sources = designspace.load_sources()?;
+        if sources.is_empty() {
+            return Err(format!("{:?} has no sources", path).into());
+        }
+        self.set_masters(sources, designspace.default_source().unwrap_or(0));
+        Ok(())
+    }
+
+    fn set_masters(&mut self, sources: Vec<(String, Ufo, PathBuf)>, active: usize) {
+        let masters: Vec<_> = sources
+            .into_iter()
+            .map(|(name, ufo, path)| Master {
+                name,
+                font: Arc::new(FontObject::new(ufo, Some(path))),
+                edited: false,
+            })
+            .collect();
+        let active = active.min(masters.len().saturating_sub(1));
+        if let Some(master) = masters.get(active) {
+            self.font = master.font.clone();
+            self.info = SimpleFontInfo::from_font(&self.font);
+        }
+        self.masters = Arc::new(masters);
+        self.active_master = active;
+        self.build_path_cache();
+    }
+
+    /// The index of the master being edited, if a designspace is open.
+    pub fn active_master(&self) -> Option<usize> {
+        if self.masters.is_empty() {
+            None
+        } else {
+            Some(self.active_master)
+        }
+    }
+
+    /// Switch to editing another master.
+    ///
+    /// Edits to the current master are kept, and open sessions are reloaded
+    /// from the new master, so that editor windows show the same glyph in
+    /// that master; a glyph the new master doesn't have is shown empty, and
+    /// is only added to it if it is edited.
+    pub fn set_active_master(&mut self, idx: usize) {
+        if idx == self.active_master || idx >= self.masters.len() {
+            return;
+        }
+        let flushed = self.flush_sessions();
+        let masters = Arc::make_mut(&mut self.masters);
+        let current = &mut masters[self.active_master];
+        // the font is shared with the master until something changes it
+        if flushed || !Arc::ptr_eq(&current.font, &self.font) {
+            current.edited = true;
+        }
+        current.font = self.font.clone();
+        self.font = masters[idx].font.clone();
+        self.active_master = idx;
         self.info = SimpleFontInfo::from_font(&self.font);
+
+        let sessions: Vec<_> = self.sessions.values().cloned().collect();
+        for session in sessions {
+            let reloaded = session.reload(self);
+            Arc::make_mut(&mut self.sessions).insert(session.id, Arc::new(reloaded));
+        }
         self.build_path_cache();
     }
 
+    /// The glyph `name` in every master but the active one that has it, with
+    /// the index of its master.
+    pub(crate) fn other_master_glyphs<'a>(
+        &'a self,
+        name: &'a GlyphName,
+    ) -> impl Iterator<Item = (usize, &'a Arc<Glyph>)> + 'a {
+        let active = self.active_master;
+        self.masters
+            .iter()
+            .enumerate()
+            .filter(move |(idx, _)| *idx != active)
+            .filter_map(move |(idx, master)| master.font.ufo.get_glyph(name).map(|g| (idx, g)))
+    }
+
+    /// Put each glyph in the master with its index, replacing the glyph of
+    /// the same name. The active master is edited through its sessions, and
+    /// is left alone.
+    pub(crate) fn set_master_glyphs(&mut self, glyphs: &[(usize, Arc<Glyph>)]) {
+        let active = self.active_master;
+        let masters = Arc::make_mut(&mut self.masters);
+        for (idx, glyph) in glyphs {
+            match masters.get_mut(*idx) {
+                Some(master) if *idx != active => {
+                    Arc::make_mut(&mut master.font)
+                        .ufo
+                        .get_default_layer_mut()
+                        .unwrap()
+                        .insert_glyph(Glyph::clone(glyph));
+                    master.edited = true;
+                }
+                _ => log::warn!("can't change '{}' in master {}", glyph.name, idx),
+            }
+        }
+    }
+
+    /// Write the font info and the glyphs of all open sessions to the UFO,
+    /// returning `true` if anything changed.
+    ///
+    /// The UFO is left untouched if nothing changed, so that it can still be
+    /// compared by pointer with the master it came from.
+    fn flush_sessions(&mut self) -> bool {
+        let mut changed = false;
+        if !SimpleFontInfo::from_font(&self.font).same(&self.info) {
+            Arc::make_mut(&mut self.font).update_info(&self.info);
+            changed = true;
+        }
+        for session in self.sessions.values() {
+            let glyph = session.to_norad_glyph();
+            let unchanged = match self.font.ufo.get_glyph(&session.name) {
+                Some(saved) => **saved == glyph,
+                // a session of a glyph the font doesn't have starts empty
+                None => glyph == Glyph::new_named(session.name.clone()),
+            };
+            if !unchanged {
+                Arc::make_mut(&mut self.font)
+                    .ufo
+                    .get_default_layer_mut()
+                    .unwrap()
+                    .insert_glyph(glyph);
+                changed = true;
+            }
+        }
+        changed
+    }
+
     fn build_path_cache(&mut self) {
         let Workspace {
             font,
@@ -152,24 +324,29 @@ impl Workspace {
     }
 
     pub fn save(&mut self) -> Result<(), Box<dyn Error>> {
-        let font_obj = Arc::make_mut(&mut self.font);
-        font_obj.update_info(&self.info);
-        if let Some(path) = font_obj.path.as_ref() {
+        self.flush_sessions();
+        if let Some(path) = self.font.path.as_ref() {
             backup_ufo_at_path(path)?;
             log::info!("saving to {:?}", path);
-            // flush all open sessions
-            for session in self.sessions.values() {
-                let glyph = session.to_norad_glyph();
-                font_obj
-                    .ufo
-                    .get_default_layer_mut()
-                    .unwrap()
-                    .insert_glyph(glyph);
-            }
-            font_obj.ufo.save(&path)?;
+            self.font.ufo.save(&path)?;
+            Arc::make_mut(&mut self.font).mark_saved();
         } else {
             log::error!("save called with no path set");
         }
+
+        // the other masters of a designspace may have been edited, too
+        let active = self.active_master;
+        for (idx, master) in Arc::make_mut(&mut self.masters).iter_mut().enumerate() {
+            if idx == active || !master.edited {
+                continue;
+            }
+            if let Some(path) = master.font.path.clone() {
+                backup_ufo_at_path(&path)?;
+                log::info!("saving master '{}' to {:?}", master.name, path);
+                master.font.ufo.save(&path)?;
+                Arc::make_mut(&mut master.font).mark_saved();
+            }
+        }
         Ok(())
     }
 
@@ -191,6 +368,46 @@ impl Workspace {
         self.cache.get(name)
     }
 
+    /// Returns the glyph with this name, preferring the version in an open
+    /// editor session, if there is one.
+    pub(crate) fn glyph(&self, name: &GlyphName) -> Option<&Arc<Glyph>> {
+        self.sessions
+            .values()
+            .find(|sesh| sesh.name == *name)
+            .map(|sesh| &sesh.glyph)
+            .or_else(|| self.font.ufo.get_glyph(name))
+    }
+
+    /// Returns `true` if the glyph `name` contains `component`, directly or
+    /// through another component.
+    pub(crate) fn uses_component(&self, name: &GlyphName, component: &GlyphName) -> bool {
+        fn visit(ws: &Workspace, name: &GlyphName, target: &GlyphName, depth: usize) -> bool {
+            // guard against existing cycles in the font.
+            if depth > MAX_COMPONENT_DEPTH {
+                return true;
+            }
+            ws.glyph(name)
+                .and_then(|glyph| glyph.outline.as_ref())
+                .map(|outline| {
+                    outline.components.iter().any(|comp| {
+                        comp.base == *target || visit(ws, &comp.base, target, depth + 1)
+                    })
+                })
+                .unwrap_or(false)
+        }
+        visit(self, name, component, 0)
+    }
+
+    /// Returns the current sidebearings of the glyph with this name.
+    pub(crate) fn sidebearings(&self, name: &GlyphName) -> Option<Sidebearings> {
+        let glyph = self.glyph(name)?;
+        let advance = glyph.advance.as_ref()?.width as f64;
+        let bounds = self.get_bezier(name)?.bounding_box();
+        let left = bounds.min_x().round();
+        let right = advance - bounds.max_x().round();
+        Some(Sidebearings { left, right })
+    }
+
     /// After a glyph is edited this rebuilds the affected beziers.
     pub(crate) fn invalidate_path(&mut self, name: &GlyphName) {
         let Workspace {
@@ -211,6 +428,40 @@ impl Workspace {
         }
     }
 
+    /// Make the sidebearings of this glyph match its metrics keys, without
+    /// opening it in an editor. Returns `true` if the glyph was changed.
+    pub(crate) fn resync_metrics_keys(&mut self, name: &GlyphName) -> bool {
+        let mismatches = metrics_keys::glyph_mismatches(self, name);
+        if mismatches.is_empty() {
+            return false;
+        }
+        let existing = self
+            .session_map
+            .get(name)
+            .and_then(|id| self.sessions.get(id));
+        let mut session = match existing {
+            Some(session) => EditSession::clone(session),
+            None => EditSession::new(name, self),
+        };
+        for mismatch in mismatches {
+            session.adjust_sidebearing(mismatch.delta(), mismatch.side == Side::Left);
+        }
+
+        // a glyph with a session is saved from the session, not the UFO
+        if self.session_map.contains_key(name) {
+            Arc::make_mut(&mut self.sessions).insert(session.id, Arc::new(session));
+        } else {
+            let glyph = session.to_norad_glyph();
+            self.font_mut()
+                .ufo
+                .get_default_layer_mut()
+                .unwrap()
+                .insert_glyph(glyph);
+        }
+        self.invalidate_path(name);
+        true
+    }
+
     /// Returns the upm for this font.
     ///
     /// This is needed to correctly scale the points in the glyph.
@@ -407,6 +658,32 @@ impl EditorState {
 }
 
 impl FontObject {
+    fn new(ufo: Ufo, path: Option<PathBuf>) -> FontObject {
+        let mut font = FontObject {
+            path: path.map(Into::into),
+            ufo,
+            saved: Default::default(),
+            placeholder: Arc::new(placeholder_outline()),
+        };
+        font.mark_saved();
+        font
+    }
+
+    /// The glyph `name` as it was when the font was last loaded or saved.
+    pub fn saved_glyph(&self, name: &GlyphName) -> Option<&Arc<Glyph>> {
+        self.saved.get(name)
+    }
+
+    /// Record the current glyphs as the saved ones.
+    fn mark_saved(&mut self) {
+        let ufo = &self.ufo;
+        let saved = ufo
+            .iter_names()
+            .filter_map(|name| ufo.get_glyph(&name).map(|glyph| (name, glyph.clone())))
+            .collect();
+        self.saved = Arc::new(saved);
+    }
+
     /// Update the actual `FontInfo` from the generated `SimpleFontInfo`
     #[allow(clippy::float_cmp)]
     fn update_info(&mut self, info: &SimpleFontInfo) {
@@ -455,11 +732,7 @@ impl Default for FontObject {
         let mut ufo = Ufo::new();
         ufo.font_info = Some(font_info);
 
-        FontObject {
-            path: None,
-            ufo,
-            placeholder: Arc::new(placeholder_outline()),
-        }
+        FontObject::new(ufo, None)
     }
 }
 
@@ -514,6 +787,73 @@ impl<'a> From<&'a FontInfo> for FontMetrics {
     }
 }
 
+impl FontMetrics {
+    /// The direction of an upright stem, following the italic angle.
+    ///
+    /// This is a unit vector pointing up, which is `(0, 1)` if there is no
+    /// italic angle.
+    pub fn italic_vertical(&self) -> Vec2 {
+        // the UFO italic angle is in degrees, counter-clockwise from vertical.
+        let angle = self.italic_angle.unwrap_or(0.0).to_radians();
+        Vec2::new(-angle.sin(), angle.cos())
+    }
+
+    /// The y position of this metric, if it is set.
+    pub fn metric(&self, metric: Metric) -> Option<f64> {
+        match metric {
+            Metric::Baseline => Some(0.0),
+            Metric::XHeight => self.x_height,
+            Metric::CapHeight => self.cap_height,
+            Metric::Ascender => self.ascender,
+            Metric::Descender => self.descender,
+        }
+    }
+
+    /// The metric closest to `y`, and its position.
+    pub fn nearest_metric(&self, y: f64) -> (Metric, f64) {
+        Metric::all()
+            .iter()
+            .filter_map(|m| self.metric(*m).map(|pos| (*m, pos)))
+            .min_by(|(_, a), (_, b)| (a - y).abs().partial_cmp(&(b - y).abs()).unwrap())
+            .unwrap_or((Metric::Baseline, 0.0))
+    }
+
+    /// The y positions of the baseline and of each metric that is set.
+    pub fn lines(&self) -> Vec<f64> {
+        let metrics = [
+            self.descender,
+            self.x_height,
+            self.cap_height,
+            self.ascender,
+        ];
+        let mut lines = vec![0.0];
+        lines.extend(metrics.iter().flatten());
+        lines
+    }
+}
+
+impl Metric {
+    pub fn all() -> &'static [Metric] {
+        &[
+            Metric::Baseline,
+            Metric::XHeight,
+            Metric::CapHeight,
+            Metric::Ascender,
+            Metric::Descender,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Baseline => "baseline",
+            Metric::XHeight => "x-height",
+            Metric::CapHeight => "cap height",
+            Metric::Ascender => "ascender",
+            Metric::Descender => "descender",
+        }
+    }
+}
+
 impl Default for FontMetrics {
     fn default() -> Self {
         FontMetrics {
@@ -979,4 +1319,52 @@ mod tests {
         assert_eq!(font_info.style_name, Some("Extra Cheese".to_string()));
         assert_eq!(font_info.descender, Some(420.0.into()));
     }
+
+    #[test]
+    fn switching_masters_only_marks_changed_ones_edited() {
+        let mut regular = Ufo::new();
+        let layer = regular.get_default_layer_mut().unwrap();
+        layer.insert_glyph(Glyph::new_named("a"));
+        let sources = vec![
+            ("Regular".into(), regular, "Regular.ufo".into()),
+            ("Bold".into(), Ufo::new(), "Bold.ufo".into()),
+        ];
+        let mut workspace = Workspace::default();
+        workspace.set_masters(sources, 0);
+
+        let session = workspace.get_or_create_session(&"a".into());
+        workspace.set_active_master(1);
+        assert!(workspace.font.ufo.get_glyph(&"a".into()).is_none());
+        assert!(workspace.sessions[&session.id].paths.is_empty());
+        workspace.set_active_master(0);
+        assert!(workspace.masters.iter().all(|master| !master.edited));
+    }
+
+    #[test]
+    fn switching_masters_keeps_saved_glyphs() {
+        let mut regular = Ufo::new();
+        let layer = regular.get_default_layer_mut().unwrap();
+        layer.insert_glyph(Glyph::new_named("a"));
+        let sources = vec![
+            ("Regular".into(), regular, "Regular.ufo".into()),
+            ("Bold".into(), Ufo::new(), "Bold.ufo".into()),
+        ];
+        let mut workspace = Workspace::default();
+        workspace.set_masters(sources, 0);
+
+        let name = GlyphName::from("a");
+        let mut session = (*workspace.get_or_create_session(&name)).clone();
+        Arc::make_mut(&mut session.glyph).advance = Some(norad::glyph::Advance {
+            width: 500.0,
+            height: 0.0,
+        });
+        Arc::make_mut(&mut workspace.sessions).insert(session.id, Arc::new(session));
+        workspace.set_active_master(1);
+        workspace.set_active_master(0);
+
+        // the edit is written to the UFO, but it hasn't been saved
+        let edited = workspace.font.ufo.get_glyph(&name).unwrap();
+        assert_eq!(edited.advance.as_ref().map(|a| a.width), Some(500.0));
+        assert!(workspace.font.saved_glyph(&name).unwrap().advance.is_none());
+    }
 }
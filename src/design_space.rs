@@ -134,6 +134,17 @@ impl DVec2 {
         }
     }
 
+    /// The vector snapped to either the horizontal axis or `vertical`, a unit
+    /// vector used in place of the vertical axis, such as for an italic angle.
+    pub fn axis_locked_to(self, vertical: Vec2) -> DVec2 {
+        let along = self.to_raw().dot(vertical);
+        if self.x.abs() > along.abs() {
+            DVec2::new(self.x, 0.0)
+        } else {
+            DVec2::from_raw(vertical * along)
+        }
+    }
+
     #[inline]
     pub(crate) fn zero_x(self) -> DVec2 {
         DVec2::new(0.0, self.y)
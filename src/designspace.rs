@@ -0,0 +1,397 @@
+//! Reading [designspace] files, which describe the masters of a multi-master
+//! project.
+//!
+//! We only read the axes and sources; instances, rules and sources that
+//! refer to a layer other than the default are ignored.
+//!
+//! [designspace]: https://fonttools.readthedocs.io/en/latest/designspaceLib/xml.html
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use norad::Ufo;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// An axis of variation.
+///
+/// The minimum, default and maximum are user-space values; `map` takes them
+/// to the design-space values that sources are located by.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    pub name: String,
+    pub tag: String,
+    pub minimum: f64,
+    pub default: f64,
+    pub maximum: f64,
+    /// Pairs of user-space and design-space values, sorted by the former.
+    pub map: Vec<(f64, f64)>,
+}
+
+/// A master, and where it is in the designspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    pub name: String,
+    /// The path of the source UFO, resolved relative to the designspace.
+    pub path: PathBuf,
+    /// The position of the source on each axis in design space, by axis
+    /// name.
+    ///
+    /// Axes that are missing are at their default.
+    pub location: Vec<(String, f64)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesignSpace {
+    pub axes: Vec<Axis>,
+    pub sources: Vec<Source>,
+}
+
+impl DesignSpace {
+    pub fn load(path: impl AsRef<Path>) -> Result<DesignSpace, Box<dyn Error>> {
+        let path = path.as_ref();
+        let xml = std::fs::read_to_string(path)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        DesignSpace::parse(&xml, base_dir)
+    }
+
+    /// Parse a designspace document; source filenames are relative to
+    /// `base_dir`.
+    pub fn parse(xml: &str, base_dir: &Path) -> Result<DesignSpace, Box<dyn Error>> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+        let mut buf = Vec::new();
+        let mut result = DesignSpace::default();
+        let mut source: Option<Source> = None;
+        // `true` between an <axis> and its end tag, where its <map>s are
+        let mut in_axis = false;
+
+        loop {
+            let (elem, is_empty) = match reader.read_event(&mut buf)? {
+                Event::Start(elem) => (elem, false),
+                Event::Empty(elem) => (elem, true),
+                Event::End(elem) => {
+                    match elem.name() {
+                        b"source" => result.sources.extend(source.take()),
+                        b"axis" => in_axis = false,
+                        _ => (),
+                    }
+                    buf.clear();
+                    continue;
+                }
+                Event::Eof => break,
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+
+            match elem.name() {
+                b"axis" => {
+                    result.axes.push(parse_axis(&reader, &elem)?);
+                    in_axis = !is_empty;
+                }
+                b"map" if in_axis => {
+                    let input = number_attr(&reader, &elem, "input")?;
+                    let output = number_attr(&reader, &elem, "output")?;
+                    let axis = result.axes.last_mut().unwrap();
+                    let idx = axis.map.iter().position(|(i, _)| *i > input);
+                    let idx = idx.unwrap_or(axis.map.len());
+                    axis.map.insert(idx, (input, output));
+                }
+                b"source" => {
+                    let new_source = parse_source(&reader, &elem, base_dir)?;
+                    if is_empty {
+                        result.sources.extend(new_source);
+                    } else {
+                        source = new_source;
+                    }
+                }
+                b"dimension" => {
+                    if let Some(source) = source.as_mut() {
+                        let name = required_attr(&reader, &elem, "name")?;
+                        match dimension_value(&reader, &elem, &name, &result.axes)? {
+                            Some(value) => source.location.push((name, value)),
+                            None => log::warn!(
+                                "ignoring the location of '{}' on '{}'",
+                                source.name,
+                                name
+                            ),
+                        }
+                    }
+                }
+                _ => (),
+            }
+            buf.clear();
+        }
+        Ok(result)
+    }
+
+    /// The index of the source at the default location, if there is one.
+    pub fn default_source(&self) -> Option<usize> {
+        self.sources.iter().position(|source| {
+            self.axes.iter().all(|axis| {
+                let value = source
+                    .location
+                    .iter()
+                    .find(|(name, _)| *name == axis.name)
+                    .map(|(_, value)| *value)
+                    .unwrap_or_else(|| axis.to_design(axis.default));
+                (value - axis.to_design(axis.default)).abs() < f64::EPSILON
+            })
+        })
+    }
+
+    /// Load the UFO of every source, returning each with its name and path.
+    pub fn load_sources(&self) -> Result<Vec<(String, Ufo, PathBuf)>, Box<dyn Error>> {
+        self.sources
+            .iter()
+            .map(|source| -> Result<_, Box<dyn Error>> {
+                let ufo = Ufo::load(&source.path)
+                    .map_err(|e| format!("failed to load {:?}: {}", source.path, e))?;
+                Ok((source.name.clone(), ufo, source.path.clone()))
+            })
+            .collect()
+    }
+}
+
+impl Axis {
+    /// Map a user-space value on this axis to design space, by linear
+    /// interpolation between the entries of `map`.
+    pub fn to_design(&self, value: f64) -> f64 {
+        let (first, last) = match (self.map.first(), self.map.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return value,
+        };
+        if value <= first.0 {
+            return first.1 - first.0 + value;
+        }
+        if value >= last.0 {
+            return last.1 - last.0 + value;
+        }
+        let (lo, hi) = self
+            .map
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .find(|(_, hi)| value <= hi.0)
+            .unwrap();
+        lo.1 + (hi.1 - lo.1) * (value - lo.0) / (hi.0 - lo.0)
+    }
+}
+
+/// The design-space value of a source's <dimension>.
+///
+/// Format 5 documents may give a `uservalue` instead of an `xvalue`; this is
+/// mapped through the axis. This is `None` if there is neither, or if the
+/// axis is unknown.
+fn dimension_value(
+    reader: &Reader<&[u8]>,
+    elem: &BytesStart,
+    name: &str,
+    axes: &[Axis],
+) -> Result<Option<f64>, Box<dyn Error>> {
+    if attr(reader, elem, "xvalue")?.is_some() {
+        return number_attr(reader, elem, "xvalue").map(Some);
+    }
+    if attr(reader, elem, "uservalue")?.is_none() {
+        return Ok(None);
+    }
+    let value = number_attr(reader, elem, "uservalue")?;
+    Ok(axes
+        .iter()
+        .find(|axis| axis.name == name)
+        .map(|axis| axis.to_design(value)))
+}
+
+/// Discrete axes, which list their `values` instead of a minimum and
+/// maximum, are given the range of those values.
+fn parse_axis(reader: &Reader<&[u8]>, elem: &BytesStart) -> Result<Axis, Box<dyn Error>> {
+    let (minimum, maximum) = match attr(reader, elem, "values")? {
+        Some(values) => {
+            let values = values
+                .split_whitespace()
+                .map(|v| v.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("bad value for 'values': '{}'", values))?;
+            if values.is_empty() {
+                return Err("<axis> has no 'values'".into());
+            }
+            let minimum = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let maximum = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (minimum, maximum)
+        }
+        None => (
+            number_attr(reader, elem, "minimum")?,
+            number_attr(reader, elem, "maximum")?,
+        ),
+    };
+    Ok(Axis {
+        name: required_attr(reader, elem, "name")?,
+        tag: required_attr(reader, elem, "tag")?,
+        minimum,
+        default: number_attr(reader, elem, "default")?,
+        maximum,
+        map: Vec::new(),
+    })
+}
+
+/// Returns `None` for sources that use a layer other than the default.
+fn parse_source(
+    reader: &Reader<&[u8]>,
+    elem: &BytesStart,
+    base_dir: &Path,
+) -> Result<Option<Source>, Box<dyn Error>> {
+    let filename = required_attr(reader, elem, "filename")?;
+    if let Some(layer) = attr(reader, elem, "layer")? {
+        log::warn!("skipping source '{}' layer '{}'", filename, layer);
+        return Ok(None);
+    }
+    let name = match attr(reader, elem, "name")? {
+        Some(name) => name,
+        None => attr(reader, elem, "stylename")?.unwrap_or_else(|| filename.clone()),
+    };
+    Ok(Some(Source {
+        name,
+        path: base_dir.join(&filename),
+        location: Vec::new(),
+    }))
+}
+
+fn attr(
+    reader: &Reader<&[u8]>,
+    elem: &BytesStart,
+    key: &str,
+) -> Result<Option<String>, Box<dyn Error>> {
+    for attr in elem.attributes() {
+        let attr = attr?;
+        if attr.key == key.as_bytes() {
+            return Ok(Some(attr.unescape_and_decode_value(reader)?));
+        }
+    }
+    Ok(None)
+}
+
+fn required_attr(
+    reader: &Reader<&[u8]>,
+    elem: &BytesStart,
+    key: &str,
+) -> Result<String, Box<dyn Error>> {
+    attr(reader, elem, key)?.ok_or_else(|| {
+        let elem_name = String::from_utf8_lossy(elem.name()).into_owned();
+        format!("<{}> is missing '{}'", elem_name, key).into()
+    })
+}
+
+fn number_attr(
+    reader: &Reader<&[u8]>,
+    elem: &BytesStart,
+    key: &str,
+) -> Result<f64, Box<dyn Error>> {
+    let value = required_attr(reader, elem, key)?;
+    value
+        .parse()
+        .map_err(|_| format!("bad value for '{}': '{}'", key, value).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_axes_and_sources() {
+        let xml = r#"<?xml version='1.0' encoding='UTF-8'?>
+<designspace format="4.0">
+  <axes>
+    <axis tag="wght" name="Weight" minimum="100" maximum="900" default="400"/>
+  </axes>
+  <sources>
+    <source filename="Light.ufo" name="Light" stylename="Light">
+      <location>
+        <dimension name="Weight" xvalue="100"/>
+      </location>
+    </source>
+    <source filename="Regular.ufo" stylename="Regular"/>
+    <source filename="Regular.ufo" name="Sparse" layer="public.sparse"/>
+  </sources>
+  <instances>
+    <instance name="Medium">
+      <location>
+        <dimension name="Weight" xvalue="500"/>
+      </location>
+    </instance>
+  </instances>
+</designspace>
+"#;
+        let space = DesignSpace::parse(xml, Path::new("fonts")).unwrap();
+        assert_eq!(space.axes.len(), 1);
+        assert_eq!(space.axes[0].tag, "wght");
+        assert_eq!(space.axes[0].default, 400.0);
+
+        assert_eq!(space.sources.len(), 2);
+        assert_eq!(space.sources[0].name, "Light");
+        assert_eq!(space.sources[0].path, Path::new("fonts/Light.ufo"));
+        assert_eq!(space.sources[0].location, vec![("Weight".into(), 100.0)]);
+        assert_eq!(space.sources[1].name, "Regular");
+        assert!(space.sources[1].location.is_empty());
+        assert_eq!(space.default_source(), Some(1));
+    }
+
+    #[test]
+    fn parse_discrete_axes() {
+        let xml = r#"<?xml version='1.0' encoding='UTF-8'?>
+<designspace format="5.0">
+  <axes>
+    <axis tag="wght" name="Weight" minimum="100" maximum="900" default="400"/>
+    <axis tag="ital" name="Italic" values="0 1" default="0"/>
+  </axes>
+  <sources>
+    <source filename="Regular.ufo" name="Regular"/>
+  </sources>
+</designspace>
+"#;
+        let space = DesignSpace::parse(xml, Path::new("")).unwrap();
+        assert_eq!(space.axes.len(), 2);
+        assert_eq!(space.axes[1].tag, "ital");
+        assert_eq!((space.axes[1].minimum, space.axes[1].maximum), (0.0, 1.0));
+        assert_eq!(space.default_source(), Some(0));
+    }
+
+    #[test]
+    fn default_source_in_design_space() {
+        let xml = r#"<?xml version='1.0' encoding='UTF-8'?>
+<designspace format="5.0">
+  <axes>
+    <axis tag="wght" name="Weight" minimum="100" maximum="900" default="400">
+      <map input="100" output="20"/>
+      <map input="900" output="180"/>
+      <map input="400" output="80"/>
+    </axis>
+  </axes>
+  <sources>
+    <source filename="Light.ufo" name="Light">
+      <location>
+        <dimension name="Weight" uservalue="100"/>
+      </location>
+    </source>
+    <source filename="Regular.ufo" name="Regular">
+      <location>
+        <dimension name="Weight" xvalue="80"/>
+      </location>
+    </source>
+    <source filename="Bold.ufo" name="Bold">
+      <location>
+        <dimension name="Weight" uservalue="650"/>
+      </location>
+    </source>
+  </sources>
+</designspace>
+"#;
+        let space = DesignSpace::parse(xml, Path::new("")).unwrap();
+        let weight = &space.axes[0];
+        assert_eq!(weight.map, vec![(100., 20.), (400., 80.), (900., 180.)]);
+        assert_eq!(weight.to_design(50.), -30.);
+        assert_eq!(space.sources[0].location, vec![("Weight".into(), 20.0)]);
+        assert_eq!(space.sources[2].location, vec![("Weight".into(), 130.0)]);
+        assert_eq!(space.default_source(), Some(1));
+    }
+}
@@ -2,16 +2,19 @@
 
 use std::sync::Arc;
 
+use crate::anchor::Anchor;
 use crate::component::Component;
 use crate::data::{FontMetrics, Workspace};
 use crate::design_space::ViewPort;
-use crate::edit_session::EditSession;
+use crate::edit_session::{EditSession, SIDEBEARING_MARKER_SIZE};
+use crate::glyph_diff::GlyphDiff;
 use crate::guides::{Guide, GuideLine};
-use crate::path::{Path, PathSeg, PointType};
+use crate::path::{EntityId, Path, PathSeg, PointType};
 use crate::selection::Selection;
+use crate::snap::{SnapKind, Snapped};
 
 use druid::kurbo::{self, Affine, BezPath, Circle, CubicBez, Line, Point, Rect, Vec2};
-use druid::piet::{Color, Piet, RenderContext};
+use druid::piet::{Color, FontFamily, Piet, RenderContext, StrokeStyle, Text, TextLayoutBuilder};
 use druid::PaintCtx;
 
 use norad::Glyph;
@@ -27,11 +30,38 @@ const OFF_CURVE_POINT_COLOR: Color = Color::rgb8(0xbb, 0xbb, 0xbb);
 const OFF_CURVE_HANDLE_COLOR: Color = Color::rgb8(0xbb, 0xbb, 0xbb);
 const DIRECTION_ARROW_COLOR: Color = Color::rgba8(0x00, 0x00, 0x00, 0x44);
 const COMPONENT_FILL_COLOR: Color = Color::rgba8(0, 0, 0, 0x44);
+const OUTLINE_FILL_COLOR: Color = Color::rgba8(0, 0, 0, 0x22);
+const OVERLAP_COLOR: Color = Color::rgba8(0xFF, 0x30, 0x30, 0xAA);
+const DIFF_ADDED_COLOR: Color = Color::rgb8(0x2E, 0xA0, 0x43);
+const DIFF_REMOVED_COLOR: Color = Color::rgb8(0xE0, 0x30, 0x30);
+const DIFF_MOVED_COLOR: Color = Color::rgb8(0xF0, 0x8C, 0x00);
+const SNAP_POINT_COLOR: Color = Color::rgb8(0x0b, 0x8b, 0xdb);
+const SNAP_GUIDE_COLOR: Color = GUIDE_COLOR;
+const SNAP_METRIC_COLOR: Color = Color::rgb8(0xF0, 0x8C, 0x00);
+const ANCHOR_COLOR: Color = Color::rgb8(0x9B, 0x30, 0xC8);
 
 const SMOOTH_RADIUS: f64 = 3.5;
 const SMOOTH_SELECTED_RADIUS: f64 = 4.;
 const OFF_CURVE_RADIUS: f64 = 2.;
 const OFF_CURVE_SELECTED_RADIUS: f64 = 2.5;
+/// The length of the line drawn across the ends of an open path.
+const OPEN_PATH_CAP_LENGTH: f64 = 12.;
+/// Half the length, in screen units, of the lines marking a snap.
+const SNAP_INDICATOR_SIZE: f64 = 10.;
+const ANCHOR_RADIUS: f64 = 5.;
+const ANCHOR_LABEL_FONT_SIZE: f64 = 10.;
+
+/// Options that change how a session is drawn.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DrawOptions {
+    /// Draw only the filled glyph, with no non-glyph items visible.
+    pub filled: bool,
+    /// Highlight regions filled by the nonzero rule but not by even-odd,
+    /// which are overlaps or contours with the wrong direction.
+    pub highlight_overlaps: bool,
+    /// Draw on-curve points only, without off-curve points or handles.
+    pub hide_handles: bool,
+}
 
 /// A context for drawing that maps between screen space and design space.
 struct DrawCtx<'a, 'b: 'a> {
@@ -81,6 +111,51 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
         let baseline = Line::new((0.0, 0.0), (hadvance, 0.0));
         let baseline = self.space.affine() * baseline;
         self.stroke(baseline, &METRICS_COLOR, 1.0);
+
+        // slanted uprights through the origin and the advance, for italics
+        let vertical = metrics.italic_vertical();
+        if vertical.x != 0.0 {
+            let style = StrokeStyle::new().dash(vec![4.0, 4.0], 0.0);
+            let slant = vertical.x / vertical.y;
+            for x in &[0.0, hadvance] {
+                let line = Line::new(
+                    (x + descender * slant, descender),
+                    (x + ascender * slant, ascender),
+                );
+                let line = self.space.affine() * line;
+                self.stroke_styled(line, &METRICS_COLOR, 1.0, &style);
+            }
+        }
+    }
+
+    /// Draw markers at the origin and the advance width, which bound the
+    /// sidebearings; the advance can be selected and dragged.
+    fn draw_sidebearing_markers(&mut self, session: &EditSession, metrics: &FontMetrics) {
+        let advance = session.advance_width();
+        let advance_selected = session.selection.contains(&EntityId::advance_width());
+        if advance_selected {
+            let upm = metrics.units_per_em;
+            let ascender = metrics.ascender.unwrap_or_else(|| (upm * 0.8).round());
+            let descender = metrics.descender.unwrap_or_else(|| -(upm * 0.2).round());
+            let line = Line::new((advance, descender), (advance, ascender));
+            let line = self.space.affine() * line;
+            self.stroke(line, &SELECTED_LINE_SEGMENT_COLOR, 4.0);
+        }
+
+        for &(x, selected) in &[(0.0, false), (advance, advance_selected)] {
+            let tip = self.space.to_screen((x, 0.0));
+            let size = SIDEBEARING_MARKER_SIZE;
+            let mut marker = BezPath::new();
+            marker.move_to(tip);
+            marker.line_to((tip.x + size * 0.6, tip.y + size));
+            marker.line_to((tip.x - size * 0.6, tip.y + size));
+            marker.close_path();
+            if selected {
+                self.fill(marker, &CORNER_POINT_COLOR);
+            } else {
+                self.stroke(marker, &METRICS_COLOR, 1.0);
+            }
+        }
     }
 
     fn draw_grid(&mut self) {
@@ -195,6 +270,16 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
         }
     }
 
+    /// Fill the glyph with the even-odd rule over the nonzero rule, so that
+    /// regions where the two differ show in `OVERLAP_COLOR`.
+    fn draw_overlaps(&mut self, session: &EditSession, font: &Workspace, fill_color: &Color) {
+        let bez = self.space.affine() * session.to_bezier_with_components(font);
+        self.fill(&bez, &OVERLAP_COLOR);
+        // punch out the nonzero fill, then redraw even-odd in the fill color
+        self.fill_even_odd(&bez, &Color::WHITE);
+        self.fill_even_odd(&bez, fill_color);
+    }
+
     fn draw_control_point_lines(&mut self, path: &Path) {
         // if there is a trailing handle (the last operation was a click_drag
         // we need to draw that from the end point, which we track here.)
@@ -235,13 +320,13 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
             Style::Close(seg) => self.draw_open_path_terminal(&seg, selected),
             Style::OffCurve => self.draw_off_curve_point(point, selected),
             Style::Smooth => self.draw_smooth_point(point, selected),
-            Style::Tangent => self.draw_smooth_point(point, selected),
+            Style::Tangent => self.draw_tangent_point(point, selected),
             Style::Corner => self.draw_corner_point(point, selected),
         }
     }
 
     fn draw_open_path_terminal(&mut self, seg: &kurbo::PathSeg, selected: bool) {
-        let cap = cap_line(seg.to_cubic(), 12.);
+        let cap = cap_line(seg.to_cubic(), OPEN_PATH_CAP_LENGTH);
         if selected {
             self.stroke(cap, &OFF_CURVE_HANDLE_COLOR, 3.0);
         } else {
@@ -250,11 +335,7 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
     }
 
     fn draw_smooth_point(&mut self, p: Point, selected: bool) {
-        let radius = if selected {
-            SMOOTH_SELECTED_RADIUS
-        } else {
-            SMOOTH_RADIUS
-        };
+        let radius = marker_radius(&Style::Smooth, selected);
         let circ = Circle::new(p, radius);
         if selected {
             self.fill(circ, &SMOOTH_POINT_COLOR);
@@ -263,12 +344,25 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
         }
     }
 
-    fn draw_corner_point(&mut self, p: Point, selected: bool) {
-        let radius = if selected {
-            SMOOTH_SELECTED_RADIUS
+    fn draw_tangent_point(&mut self, p: Point, selected: bool) {
+        let radius = marker_radius(&Style::Tangent, selected);
+        // a diamond with the same area as the corner point square
+        let radius = radius * std::f64::consts::SQRT_2;
+        let mut diamond = BezPath::new();
+        diamond.move_to((p.x, p.y - radius));
+        diamond.line_to((p.x + radius, p.y));
+        diamond.line_to((p.x, p.y + radius));
+        diamond.line_to((p.x - radius, p.y));
+        diamond.close_path();
+        if selected {
+            self.fill(diamond, &SMOOTH_POINT_COLOR);
         } else {
-            SMOOTH_RADIUS
-        };
+            self.stroke(diamond, &SMOOTH_POINT_COLOR, 1.0);
+        }
+    }
+
+    fn draw_corner_point(&mut self, p: Point, selected: bool) {
+        let radius = marker_radius(&Style::Corner, selected);
         let rect = Rect::new(p.x - radius, p.y - radius, p.x + radius, p.y + radius);
         if selected {
             self.fill(rect, &CORNER_POINT_COLOR);
@@ -278,11 +372,7 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
     }
 
     fn draw_off_curve_point(&mut self, p: Point, selected: bool) {
-        let radius = if selected {
-            OFF_CURVE_SELECTED_RADIUS
-        } else {
-            OFF_CURVE_RADIUS
-        };
+        let radius = marker_radius(&Style::OffCurve, selected);
         let circ = Circle::new(p, radius);
         if selected {
             self.fill(circ, &OFF_CURVE_POINT_COLOR);
@@ -307,6 +397,41 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
         self.fill(arrow, &DIRECTION_ARROW_COLOR);
     }
 
+    /// Draw an anchor as a circle with a cross through it, with its name.
+    fn draw_anchor(&mut self, anchor: &Anchor, selected: bool) {
+        let p = anchor.point.to_screen(self.space);
+        let circ = Circle::new(p, ANCHOR_RADIUS);
+        if selected {
+            self.fill(circ, &ANCHOR_COLOR);
+        } else {
+            self.stroke(circ, &ANCHOR_COLOR, 1.0);
+        }
+        let reach = ANCHOR_RADIUS + 2.0;
+        let horiz = Line::new((p.x - reach, p.y), (p.x + reach, p.y));
+        let vert = Line::new((p.x, p.y - reach), (p.x, p.y + reach));
+        self.stroke(horiz, &ANCHOR_COLOR, 1.0);
+        self.stroke(vert, &ANCHOR_COLOR, 1.0);
+
+        if !anchor.name.is_empty() {
+            let layout = self
+                .text()
+                .new_text_layout(anchor.name.clone())
+                .font(FontFamily::SYSTEM_UI, ANCHOR_LABEL_FONT_SIZE)
+                .text_color(ANCHOR_COLOR)
+                .build()
+                .unwrap();
+            self.draw_text(&layout, p + Vec2::new(reach + 2.0, -reach - 4.0));
+        }
+    }
+
+    fn draw_component_bounds(&mut self, component: &Component) {
+        if let Some(bounds) = component.bounding_box() {
+            let bounds = self.space.rect_to_screen(bounds);
+            let style = StrokeStyle::new().dash(vec![2.0, 4.0], 0.0);
+            self.stroke_styled(bounds, &SELECTED_LINE_SEGMENT_COLOR, 1.0, &style);
+        }
+    }
+
     fn draw_component(&mut self, component: &Component, font: &Workspace, color: Color) {
         if let Some(mut bez) = font.get_bezier(&component.base) {
             let bez = Arc::make_mut(&mut bez);
@@ -406,17 +531,26 @@ pub(crate) fn draw_session(
     metrics: &FontMetrics,
     session: &EditSession,
     font: &Workspace,
-    is_preview: bool,
+    options: DrawOptions,
 ) {
     let mut draw_ctx = DrawCtx::new(&mut ctx.render_ctx, space, visible_rect);
 
-    if is_preview {
-        draw_ctx.draw_filled(session, font);
+    if options.filled {
+        if options.highlight_overlaps {
+            draw_ctx.draw_overlaps(session, font, &Color::BLACK);
+        } else {
+            draw_ctx.draw_filled(session, font);
+        }
         return;
     }
 
+    // this paints over the canvas, so it goes first
+    if options.highlight_overlaps {
+        draw_ctx.draw_overlaps(session, font, &OUTLINE_FILL_COLOR);
+    }
     draw_ctx.draw_grid();
     draw_ctx.draw_metrics(&session.glyph, metrics);
+    draw_ctx.draw_sidebearing_markers(session, metrics);
     draw_ctx.draw_guides(&session.guides, &session.selection);
 
     for path in session.paths.iter() {
@@ -426,15 +560,19 @@ pub(crate) fn draw_session(
         }
         let bez = space.affine() * path.bezier();
         draw_ctx.draw_path(&bez);
-        draw_ctx.draw_control_point_lines(path);
+        if !options.hide_handles {
+            draw_ctx.draw_control_point_lines(path);
+        }
         draw_ctx.draw_direction_indicator(&bez);
 
         for point in PointIter::new(path, space, &bez, &session.selection) {
-            draw_ctx.draw_point(point)
+            if !(options.hide_handles && matches!(point.style, Style::OffCurve)) {
+                draw_ctx.draw_point(point)
+            }
         }
 
         if let Some(pt) = path.trailing() {
-            if path.should_draw_trailing() {
+            if path.should_draw_trailing() && !options.hide_handles {
                 draw_ctx.draw_off_curve_point(pt.to_screen(space), true);
             }
         }
@@ -442,6 +580,79 @@ pub(crate) fn draw_session(
 
     for component in session.components.iter() {
         draw_ctx.draw_component(component, font, COMPONENT_FILL_COLOR);
+        if session.selection.contains(&component.id) {
+            draw_ctx.draw_component_bounds(component);
+        }
+    }
+
+    for anchor in session.anchors.iter() {
+        draw_ctx.draw_anchor(anchor, session.selection.contains(&anchor.id));
+    }
+}
+
+/// Draw the filled glyph, optionally with its metrics, for exporting as an
+/// image.
+pub(crate) fn draw_glyph_image(
+    ctx: &mut Piet,
+    space: ViewPort,
+    visible_rect: Rect,
+    metrics: &FontMetrics,
+    session: &EditSession,
+    font: &Workspace,
+    show_metrics: bool,
+) {
+    let mut draw_ctx = DrawCtx::new(ctx, space, visible_rect);
+    if show_metrics {
+        draw_ctx.draw_metrics(&session.glyph, metrics);
+    }
+    draw_ctx.draw_filled(session, font);
+}
+
+/// Draw the saved version of a glyph as a ghost outline, and mark the points
+/// that have been added, removed or moved since.
+pub(crate) fn draw_glyph_diff(
+    ctx: &mut PaintCtx,
+    diff: &GlyphDiff,
+    space: ViewPort,
+    ghost_color: &Color,
+) {
+    ctx.stroke(space.affine() * &*diff.saved, ghost_color, 1.0);
+    for point in diff.added.iter() {
+        let circle = Circle::new(point.to_screen(space), SMOOTH_SELECTED_RADIUS);
+        ctx.fill(circle, &DIFF_ADDED_COLOR);
+    }
+    for point in diff.removed.iter() {
+        let circle = Circle::new(point.to_screen(space), SMOOTH_SELECTED_RADIUS);
+        ctx.stroke(circle, &DIFF_REMOVED_COLOR, 1.5);
+    }
+    for (from, to) in diff.moved.iter() {
+        let (from, to) = (from.to_screen(space), to.to_screen(space));
+        ctx.stroke(Line::new(from, to), &DIFF_MOVED_COLOR, 1.0);
+        ctx.fill(Circle::new(from, OFF_CURVE_RADIUS), &DIFF_MOVED_COLOR);
+    }
+}
+
+/// Mark a snapped position, with a line across each axis that snapped,
+/// colored by the kind of target it snapped to.
+pub(crate) fn draw_snap_indicator(ctx: &mut PaintCtx, snapped: &Snapped, space: ViewPort) {
+    let color = |kind: SnapKind| match kind {
+        SnapKind::Point => &SNAP_POINT_COLOR,
+        SnapKind::Guide => &SNAP_GUIDE_COLOR,
+        SnapKind::Metric => &SNAP_METRIC_COLOR,
+    };
+    let center = snapped.point.to_screen(space);
+    if let Some(kind) = snapped.x {
+        let offset = Vec2::new(0., SNAP_INDICATOR_SIZE);
+        let line = Line::new(center - offset, center + offset);
+        ctx.stroke(line, color(kind), 1.0);
+    }
+    if let Some(kind) = snapped.y {
+        let offset = Vec2::new(SNAP_INDICATOR_SIZE, 0.);
+        let line = Line::new(center - offset, center + offset);
+        ctx.stroke(line, color(kind), 1.0);
+    }
+    if snapped.is_snapped() {
+        ctx.stroke(Circle::new(center, SMOOTH_RADIUS), &PATH_COLOR, 1.0);
     }
 }
 
@@ -456,6 +667,19 @@ fn tangent_vector(t: f64, cb: CubicBez) -> Vec2 {
         + 3.0 * t.powi(2) * (p3 - p2)
 }
 
+/// The radius, in screen units, of the marker drawn for a point.
+///
+/// Point markers are drawn in screen space, so that they stay the same size
+/// at any zoom level.
+fn marker_radius(style: &Style, selected: bool) -> f64 {
+    match (style, selected) {
+        (Style::OffCurve, true) => OFF_CURVE_SELECTED_RADIUS,
+        (Style::OffCurve, false) => OFF_CURVE_RADIUS,
+        (_, true) => SMOOTH_SELECTED_RADIUS,
+        (_, false) => SMOOTH_RADIUS,
+    }
+}
+
 /// Create a line of length `len` perpendicular to the tangent of the cubic
 /// bezier `cb`, centered on the bezier's start point.
 fn cap_line(cb: CubicBez, len: f64) -> Line {
@@ -1,18 +1,22 @@
 use std::collections::BTreeSet;
 use std::sync::Arc;
 
-use druid::kurbo::{BezPath, ParamCurveNearest, Point, Rect, Shape, Size, Vec2};
+use druid::kurbo::{Affine, BezPath, ParamCurveNearest, Point, Rect, Shape, Size, Vec2};
 use druid::{Data, Lens};
-use norad::glyph::Outline;
+use norad::glyph::{Advance, Contour, Identifier, Outline};
 use norad::{Glyph, GlyphName};
+use plist::{Dictionary, Value};
 
+use crate::anchor::Anchor;
 use crate::component::Component;
-use crate::data::Workspace;
+use crate::data::{self, FontMetrics, Metric, Workspace};
 use crate::design_space::{DPoint, DVec2, ViewPort};
-use crate::guides::Guide;
-use crate::path::{EntityId, Path, PathPoint, PathSeg};
+use crate::glyph_diff::{self, GlyphDiff};
+use crate::guides::{Guide, GuideLine, GuideSpec};
+use crate::path::{EntityId, Path, PathPoint, PathSeg, PointType};
 use crate::quadrant::Quadrant;
 use crate::selection::Selection;
+use crate::snap::{self, SnapConfig, SnapKind, Snapped, SNAP_DISTANCE};
 
 /// Minimum distance in screen units that a click must occur to be considered
 /// on a point?
@@ -21,6 +25,14 @@ pub const MIN_CLICK_DISTANCE: f64 = 10.0;
 /// Amount of bias penalizing on-curve points; we want to break ties in favor
 /// of off-curve.
 pub const ON_CURVE_PENALTY: f64 = MIN_CLICK_DISTANCE / 2.0;
+/// The height, in screen units, of the triangles under the baseline that mark
+/// the origin and the advance, which is grabbed by its marker.
+pub const SIDEBEARING_MARKER_SIZE: f64 = 7.;
+/// How far, in design units, a paste is offset when it would land exactly on
+/// top of existing paths.
+const PASTE_OFFSET: f64 = 10.0;
+/// The largest overshoot we recognise, as a fraction of the em.
+const MAX_OVERSHOOT: f64 = 0.05;
 
 /// A unique identifier for a session. A session keeps the same identifier
 /// even if the name of the glyph changes.
@@ -46,11 +58,106 @@ pub struct EditSession {
     pub selection: Selection,
     pub components: Arc<Vec<Component>>,
     pub guides: Arc<Vec<Guide>>,
+    pub anchors: Arc<Vec<Anchor>>,
     pub viewport: ViewPort,
+    /// The font's metrics, kept in sync by the editor, for snapping.
+    pub metrics: FontMetrics,
+    /// The enabled snap targets, kept in sync with the settings by the editor.
+    pub snap: SnapConfig,
+    /// If `true`, moving an on-curve point scales the handles of its curves
+    /// with their length, instead of moving them rigidly. Kept in sync with
+    /// the settings by the editor.
+    pub proportional_handles: bool,
+    /// The spacing of the grid that moved points snap to, or `None` if grid
+    /// snapping is off. Kept in sync by the editor.
+    pub grid: Option<f64>,
+    /// If `true`, off-curve points are hidden, and can't be clicked or
+    /// selected with a selection rectangle. Kept in sync by the editor.
+    pub hide_handles: bool,
+    /// The paths from before the selection was first smoothed, and the paths
+    /// the last smoothing left, so that repeated smoothing is limited by how
+    /// far points are from where they started.
+    #[data(ignore)]
+    smoothing: Option<(Arc<Vec<Path>>, Arc<Vec<Path>>)>,
     work_bounds: Rect,
     quadrant: Quadrant,
 }
 
+/// An edit that moved points, in a form that can be repeated in other masters.
+///
+/// Points are matched by their UFO identifiers, if they have them, and
+/// otherwise by their order in the outline. Offsets are scaled by the size
+/// of the contour in each master, so that a move in a light master makes a
+/// proportionally larger one in a bold master.
+#[derive(Debug, Clone)]
+pub struct MasterEdit {
+    /// For each contour, the identifier and offset of each point.
+    contours: Vec<Vec<(Option<Identifier>, Vec2)>>,
+    /// The bounds of each contour's points before the edit.
+    bounds: Vec<Rect>,
+}
+
+/// The glyphs changed in other masters by `apply_to_all_masters`, before and
+/// after the change, each with the index of its master.
+#[derive(Debug, Clone, Default)]
+pub struct MasterChange {
+    pub before: Vec<(usize, Arc<Glyph>)>,
+    pub after: Vec<(usize, Arc<Glyph>)>,
+}
+
+/// Where pasted geometry should be placed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PasteMode {
+    /// At the coordinates it was copied from.
+    InPlace,
+    /// Centered on this point, such as the cursor position.
+    Centered(DPoint),
+}
+
+/// An axis to flip or distribute the selection along.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+    /// Along x; a horizontal flip mirrors points across a vertical line.
+    Horizontal,
+    /// Along y.
+    Vertical,
+}
+
+/// Which edge or center of their bounding box points are aligned to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlignKind {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// The points share the x coordinate of the center.
+    HorizontalCenter,
+    /// The points share the y coordinate of the center.
+    VerticalCenter,
+}
+
+/// A kind of point, for selecting all points of that kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointKind {
+    /// On-curve points that are not smooth.
+    Corner,
+    Smooth,
+    /// Any on-curve point.
+    OnCurve,
+    OffCurve,
+}
+
+impl PointKind {
+    fn matches(self, typ: PointType) -> bool {
+        match self {
+            PointKind::Corner => typ == PointType::OnCurve,
+            PointKind::Smooth => typ == PointType::OnCurveSmooth,
+            PointKind::OnCurve => typ.is_on_curve(),
+            PointKind::OffCurve => !typ.is_on_curve(),
+        }
+    }
+}
+
 /// A type that is only created by a lens, for our coordinate editing panel
 #[derive(Debug, Clone, Copy, Data, Lens)]
 pub struct CoordinateSelection {
@@ -59,6 +166,8 @@ pub struct CoordinateSelection {
     /// the bounding box of the selection
     pub frame: Rect,
     pub quadrant: Quadrant,
+    /// The distance of the selected extrema past their nearest metric
+    pub overshoot: Option<(Metric, f64)>,
 }
 
 impl EditSession {
@@ -68,7 +177,14 @@ impl EditSession {
 
     pub fn new(name: &GlyphName, glyphs: &Workspace) -> Self {
         let name = name.to_owned();
-        let glyph = glyphs.font.ufo.get_glyph(&name).unwrap().to_owned();
+        // a glyph missing from the font, such as in another master, is
+        // edited as an empty one
+        let glyph = glyphs
+            .font
+            .ufo
+            .get_glyph(&name)
+            .cloned()
+            .unwrap_or_else(|| Arc::new(Glyph::new_named(name.clone())));
         let paths: Vec<Path> = glyph
             .outline
             .as_ref()
@@ -84,6 +200,11 @@ impl EditSession {
             .as_ref()
             .map(|guides| guides.iter().map(Guide::from_norad).collect())
             .unwrap_or_default();
+        let anchors = glyph
+            .anchors
+            .as_ref()
+            .map(|anchors| anchors.iter().map(Anchor::from_norad).collect())
+            .unwrap_or_default();
 
         //FIXME: this is never updated, and shouldn't be relied on
         let work_bounds = glyphs
@@ -99,12 +220,97 @@ impl EditSession {
             selection: Selection::new(),
             components: Arc::new(components),
             guides: Arc::new(guides),
+            anchors: Arc::new(anchors),
             viewport: ViewPort::default(),
+            metrics: glyphs.info.metrics.clone(),
+            snap: SnapConfig::default(),
+            proportional_handles: false,
+            grid: None,
+            hide_handles: false,
+            smoothing: None,
             quadrant: Quadrant::Center,
             work_bounds,
         }
     }
 
+    /// Load this session's glyph again from `glyphs`, such as after switching
+    /// to another master, keeping the session's id and view.
+    pub fn reload(&self, glyphs: &Workspace) -> Self {
+        let mut session = EditSession::new(&self.name, glyphs);
+        session.id = self.id;
+        session.viewport = self.viewport;
+        session.snap = self.snap.clone();
+        session.proportional_handles = self.proportional_handles;
+        session.grid = self.grid;
+        session.hide_handles = self.hide_handles;
+        session
+    }
+
+    /// The points moved since `before`, if the outline has not otherwise
+    /// changed and any point moved.
+    pub fn edit_since(&self, before: &EditSession) -> Option<MasterEdit> {
+        if before.paths.len() != self.paths.len() {
+            return None;
+        }
+        let mut contours = Vec::new();
+        let mut bounds = Vec::new();
+        for (old, new) in before.paths.iter().zip(self.paths.iter()) {
+            let (old, new) = (old.to_norad(), new.to_norad());
+            if old.points.len() != new.points.len() {
+                return None;
+            }
+            bounds.push(point_bounds(&old));
+            let offsets = old
+                .points
+                .iter()
+                .zip(new.points.iter())
+                .map(|(old, new)| {
+                    let offset = Vec2::new((new.x - old.x).into(), (new.y - old.y).into());
+                    (new.identifier.clone(), offset)
+                })
+                .collect();
+            contours.push(offsets);
+        }
+        let edit = MasterEdit { contours, bounds };
+        if edit.is_empty() {
+            None
+        } else {
+            Some(edit)
+        }
+    }
+
+    /// Repeat `edit` on this glyph in every other master of `workspace`,
+    /// returning the glyphs it changed; these are put in the masters with
+    /// `Workspace::set_master_glyphs`.
+    ///
+    /// Masters without this glyph, or where its outline is not compatible,
+    /// are left alone.
+    pub fn apply_to_all_masters(&self, edit: &MasterEdit, workspace: &Workspace) -> MasterChange {
+        let mut change = MasterChange::default();
+        for (idx, glyph) in workspace.other_master_glyphs(&self.name) {
+            let mut edited = Glyph::clone(glyph);
+            if edit.apply(&mut edited) {
+                change.before.push((idx, glyph.clone()));
+                change.after.push((idx, Arc::new(edited)));
+            }
+        }
+        change
+    }
+
+    /// Compare this glyph's outline with the version last loaded or saved.
+    pub fn diff_against_saved(&self, font: &Workspace) -> GlyphDiff {
+        let saved = font.font.saved_glyph(&self.name);
+        let saved_contours = saved
+            .and_then(|glyph| glyph.outline.as_ref())
+            .map(|outline| outline.contours.as_slice())
+            .unwrap_or(&[]);
+        let saved_outline = saved
+            .and_then(|glyph| data::path_for_glyph(glyph))
+            .unwrap_or_default();
+        let current: Vec<_> = self.paths.iter().map(Path::to_norad).collect();
+        glyph_diff::diff_contours(saved_contours, &current, saved_outline)
+    }
+
     /// Construct a bezier of the paths in this glyph, ignoring components.
     pub fn to_bezier(&self) -> BezPath {
         let mut bez = BezPath::new();
@@ -114,6 +320,19 @@ impl EditSession {
         bez
     }
 
+    /// The outline of the glyph, including its components.
+    pub fn to_bezier_with_components(&self, font: &Workspace) -> BezPath {
+        let mut bez = self.to_bezier();
+        for component in self.components.iter() {
+            if let Some(base) = font.get_bezier(&component.base) {
+                for el in (component.transform * (*base).clone()).elements() {
+                    bez.push(*el);
+                }
+            }
+        }
+        bez
+    }
+
     pub fn rebuild_glyph(&mut self) {
         let new_glyph = self.to_norad_glyph();
         *Arc::make_mut(&mut self.glyph) = new_glyph;
@@ -150,6 +369,10 @@ impl EditSession {
         Arc::make_mut(&mut self.guides)
     }
 
+    pub fn anchors_mut(&mut self) -> &mut Vec<Anchor> {
+        Arc::make_mut(&mut self.anchors)
+    }
+
     pub fn iter_points(&self) -> impl Iterator<Item = &PathPoint> {
         self.paths.iter().flat_map(|p| p.points().iter())
     }
@@ -192,11 +415,19 @@ impl EditSession {
     */
 
     /// Find the best hit, considering all items.
+    ///
+    /// Like the other hit-testing methods, `max_dist` is in screen units, and
+    /// defaults to `MIN_CLICK_DISTANCE`; the reach of a click is therefore the
+    /// same at every zoom level.
     pub fn hit_test_all(&self, point: Point, max_dist: Option<f64>) -> Option<EntityId> {
-        if let Some(hit) = self.hit_test_filtered(point, max_dist, |_| true) {
+        let visible = |p: &PathPoint| p.is_on_curve() || !self.hide_handles;
+        if let Some(hit) = self.hit_test_filtered(point, max_dist, visible) {
             return Some(hit);
         }
         let max_dist = max_dist.unwrap_or(MIN_CLICK_DISTANCE);
+        if let Some(hit) = self.hit_test_anchors(point, max_dist) {
+            return Some(hit);
+        }
         let mut best = None;
         for g in &*self.guides {
             let dist = g.screen_dist(self.viewport, point);
@@ -204,9 +435,37 @@ impl EditSession {
                 best = Some((dist, g.id))
             }
         }
+        // the advance is grabbed by its marker below the baseline, so that
+        // it doesn't get in the way of outlines that reach it
+        let marker = self.viewport.to_screen((self.advance_width(), 0.0))
+            + Vec2::new(0.0, SIDEBEARING_MARKER_SIZE * 0.5);
+        let dist = marker.distance(point);
+        if dist < max_dist && best.map(|(d, _id)| dist < d).unwrap_or(true) {
+            best = Some((dist, EntityId::advance_width()))
+        }
         best.map(|(_dist, id)| id)
     }
 
+    /// The anchor nearest to this screen point, if any is within `max_dist`.
+    fn hit_test_anchors(&self, point: Point, max_dist: f64) -> Option<EntityId> {
+        self.anchors
+            .iter()
+            .map(|a| (a.point.to_screen(self.viewport).distance(point), a.id))
+            .filter(|(dist, _)| *dist < max_dist)
+            .min_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap())
+            .map(|(_, id)| id)
+    }
+
+    /// The topmost component whose outline contains this screen point.
+    pub fn hit_test_components(&self, point: Point) -> Option<EntityId> {
+        let point = self.viewport.inverse_affine() * point;
+        self.components
+            .iter()
+            .rev()
+            .find(|c| c.contains(point))
+            .map(|c| c.id)
+    }
+
     /// Hit test a point against points.
     ///
     /// This method finds the closest point, but applies a penalty to prioritize
@@ -241,6 +500,8 @@ impl EditSession {
     }
 
     /// Hit test a point against the path segments.
+    ///
+    /// `max_dist` is in screen units, as with `hit_test_all`.
     pub fn hit_test_segments(&self, point: Point, max_dist: Option<f64>) -> Option<(PathSeg, f64)> {
         let max_dist = max_dist.unwrap_or(MIN_CLICK_DISTANCE);
         let dpt = self.viewport.from_screen(point);
@@ -292,6 +553,38 @@ impl EditSession {
             .and_then(|path| path.path_point_for_id(id))
     }
 
+    /// The UFO identifier of this point, if it has one.
+    pub fn point_identifier(&self, point: EntityId) -> Option<&Identifier> {
+        self.path_for_point(point)
+            .and_then(|path| path.point_identifier(point))
+    }
+
+    /// Set or clear the UFO identifier of this point.
+    ///
+    /// Identifiers must be unique within a glyph; returns `false` if the point
+    /// doesn't exist or another point already uses this identifier.
+    pub fn set_point_identifier(
+        &mut self,
+        point: EntityId,
+        identifier: Option<Identifier>,
+    ) -> bool {
+        if let Some(identifier) = identifier.as_ref() {
+            if self.point_identifier(point) == Some(identifier) {
+                return true;
+            }
+            if self.paths.iter().any(|p| p.uses_identifier(identifier)) {
+                return false;
+            }
+        }
+        match self.path_for_point_mut(point) {
+            Some(path) => {
+                path.set_point_identifier(point, identifier);
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn path_for_point(&self, point: EntityId) -> Option<&Path> {
         self.path_idx_for_point(point)
             .and_then(|idx| self.paths.get(idx))
@@ -302,7 +595,7 @@ impl EditSession {
         self.paths_mut().get_mut(idx)
     }
 
-    fn path_idx_for_point(&self, point: EntityId) -> Option<usize> {
+    pub(crate) fn path_idx_for_point(&self, point: EntityId) -> Option<usize> {
         self.paths.iter().position(|p| p.contains(&point))
     }
 
@@ -315,6 +608,61 @@ impl EditSession {
         self.selection.select_one(point);
     }
 
+    /// Paste paths from the clipboard, and select them.
+    pub fn paste(&mut self, mut paths: Vec<Path>, mode: PasteMode) {
+        if let PasteMode::Centered(point) = mode {
+            let bounds = paths
+                .iter()
+                .map(|path| path.bezier().bounding_box())
+                .fold(None, |acc: Option<Rect>, rect| {
+                    Some(acc.map(|acc| acc.union(rect)).unwrap_or(rect))
+                });
+            if let Some(bounds) = bounds {
+                let delta = point - DPoint::from_raw(bounds.center());
+                for path in paths.iter_mut() {
+                    path.nudge_all_points(delta);
+                }
+            }
+        }
+
+        // pasting back into the glyph the paths were copied from would hide
+        // them under the originals (or under an earlier paste), so we step
+        // them down and to the right until they are visible.
+        let offset = DVec2::from_raw((PASTE_OFFSET, -PASTE_OFFSET));
+        while !paths.is_empty() && paths.iter().all(|path| self.has_matching_path(path)) {
+            for path in paths.iter_mut() {
+                path.nudge_all_points(offset);
+            }
+        }
+        self.paste_paths(paths);
+    }
+
+    /// Returns `true` if one of our paths has the same points as `other`.
+    fn has_matching_path(&self, other: &Path) -> bool {
+        self.paths.iter().any(|path| {
+            path.is_closed() == other.is_closed()
+                && path.points().len() == other.points().len()
+                && path
+                    .points()
+                    .iter()
+                    .zip(other.points())
+                    .all(|(p1, p2)| p1.point == p2.point && p1.typ == p2.typ)
+        })
+    }
+
+    /// Add a copy of the selected points and the segments between them, as
+    /// new paths, and select the copy.
+    pub fn duplicate_selection(&mut self) {
+        let copies: Vec<_> = self
+            .paths_for_selection()
+            .iter()
+            .map(Path::duplicate)
+            .collect();
+        if !copies.is_empty() {
+            self.paste_paths(copies);
+        }
+    }
+
     pub fn paste_paths(&mut self, paths: Vec<Path>) {
         self.selection.clear();
         self.selection
@@ -350,6 +698,162 @@ impl EditSession {
         }
     }
 
+    /// Toggle the selected on-curve points between smooth and corner points.
+    ///
+    /// If any of them is a corner, they all become smooth; otherwise they all
+    /// become corners. Returns `true` if any point changed.
+    pub fn toggle_selection_smooth(&mut self) -> bool {
+        let on_curve: Vec<_> = self
+            .selection
+            .iter()
+            .filter_map(|id| self.path_point_for_id(*id))
+            .filter(|p| p.is_on_curve())
+            .collect();
+        let smooth = on_curve.iter().any(|p| p.typ != PointType::OnCurveSmooth);
+        let mut changed = false;
+        for point in on_curve {
+            if let Some(path) = self.path_for_point_mut(point.id) {
+                changed |= path.set_point_smooth(point.id, smooth);
+            }
+        }
+        changed
+    }
+
+    /// Toggle each selected point that joins a line and a curve between
+    /// a corner and a tangent point.
+    pub fn toggle_selected_tangent(&mut self) {
+        for path_points in self.selection.per_path_selection().iter() {
+            if let Some(path) = self.path_for_point_mut(path_points[0]) {
+                for point in path_points {
+                    path.toggle_tangent_point_type(*point);
+                }
+            }
+        }
+    }
+
+    /// Make the curves through the selected smooth points a little fairer.
+    ///
+    /// This can be repeated; each pass has less effect than the last.
+    pub fn smooth_selection(&mut self) {
+        // keep measuring from the original paths, unless they have been
+        // edited some other way since we last smoothed them
+        let original = match &self.smoothing {
+            Some((original, smoothed)) if Arc::ptr_eq(smoothed, &self.paths) => original.clone(),
+            _ => self.paths.clone(),
+        };
+        for path_points in self.selection.per_path_selection().iter() {
+            let start = original.iter().find(|p| p.contains(&path_points[0]));
+            if let (Some(start), Some(path)) = (start, self.path_for_point_mut(path_points[0])) {
+                path.smooth_points(path_points, start);
+            }
+        }
+        self.smoothing = Some((original, self.paths.clone()));
+    }
+
+    /// Remove off-curve points that are not part of a valid curve segment,
+    /// such as those left by a bad import, returning how many were removed.
+    pub fn remove_stray_offcurves(&mut self) -> usize {
+        let removed = self
+            .paths_mut()
+            .iter_mut()
+            .map(Path::remove_stray_off_curves)
+            .sum();
+        if removed > 0 {
+            self.selection.clear();
+        }
+        removed
+    }
+
+    /// If exactly two endpoints of open contours are selected, join them,
+    /// returning `true` if anything changed.
+    ///
+    /// The ends of two contours are joined into a single contour; the two
+    /// ends of one contour close it. Coincident ends are welded into a
+    /// single point; otherwise they are joined with a line. The points at
+    /// the join are selected.
+    pub fn join_selection(&mut self) -> bool {
+        let ids = self.selected_ids();
+        let (id1, id2) = match ids.as_slice() {
+            [id1, id2] => (*id1, *id2),
+            _ => return false,
+        };
+        let is_endpoint = |id: EntityId| {
+            self.path_for_point(id)
+                .filter(|path| !path.is_closed())
+                .map(|path| {
+                    let points = path.points();
+                    points[0].id == id || points[points.len() - 1].id == id
+                })
+                .unwrap_or(false)
+        };
+        if !is_endpoint(id1) || !is_endpoint(id2) {
+            return false;
+        }
+
+        let idx1 = self.path_idx_for_point(id1).unwrap();
+        let idx2 = self.path_idx_for_point(id2).unwrap();
+        if idx1 == idx2 {
+            self.paths_mut()[idx1].close_joining_ends();
+            // if the ends were welded, one of them is gone
+            let path = &self.paths[idx1];
+            let kept: Selection = [id1, id2]
+                .iter()
+                .copied()
+                .filter(|id| path.path_point_for_id(*id).is_some())
+                .collect();
+            self.selection = kept;
+            return true;
+        }
+
+        // the first path ends at the join, and the second starts there
+        let mut other = self.paths[idx2].clone();
+        if other.points()[0].id != id2 {
+            other.reverse_contour();
+        }
+        let paths = self.paths_mut();
+        if paths[idx1].points()[0].id == id1 && paths[idx1].points().len() > 1 {
+            paths[idx1].reverse_contour();
+        }
+        let (before, after) = paths[idx1].append_path(&other);
+        paths.remove(idx2);
+        self.selection = [before, after].iter().copied().collect();
+        true
+    }
+
+    /// Close every open contour, merging coincident endpoints or joining
+    /// them with a line, and return how many were closed.
+    pub fn close_all_open_contours(&mut self) -> usize {
+        let closed = self
+            .paths_mut()
+            .iter_mut()
+            .filter(|path| path.close_joining_ends())
+            .count();
+        if closed > 0 {
+            // forget any merged endpoints
+            let paths = self.paths.clone();
+            self.selection = self
+                .selection
+                .iter()
+                .copied()
+                .filter(|id| match paths.iter().find(|p| p.contains(id)) {
+                    Some(path) => path.path_point_for_id(*id).is_some(),
+                    None => true,
+                })
+                .collect();
+        }
+        closed
+    }
+
+    /// Balance the handles of the selected smooth points, so that the
+    /// curvature is continuous across them.
+    pub fn make_g2_continuous_at_selection(&mut self) {
+        for path_points in self.selection.per_path_selection().iter() {
+            if let Some(path) = self.path_for_point_mut(path_points[0]) {
+                path.make_g2_continuous(path_points);
+            }
+        }
+    }
+
     /// if a guide his horizontal or vertical, toggle between the two.
     pub fn toggle_guide(&mut self, id: EntityId, pos: Point) {
         let pos = self.viewport.from_screen(pos);
@@ -358,6 +862,205 @@ impl EditSession {
         }
     }
 
+    /// Move the guide with this `id` by `delta`.
+    pub fn move_guide(&mut self, id: EntityId, delta: DVec2) {
+        if let Some(guide) = self.guides_mut().iter_mut().find(|g| g.id == id) {
+            guide.nudge(delta);
+        }
+    }
+
+    /// Turn the selected angled guides `degrees` counter-clockwise about
+    /// their positions, returning `true` if any were selected.
+    pub fn rotate_selected_guides(&mut self, degrees: f64) -> bool {
+        let selection = self.selection.clone();
+        let mut rotated = false;
+        for guide in self.guides_mut().iter_mut() {
+            if let GuideLine::Angle { p1, p2 } = guide.guide {
+                if selection.contains(&guide.id) {
+                    let angle = (p2 - p1).to_raw().atan2().to_degrees();
+                    guide.guide = GuideLine::through(p1, angle + degrees);
+                    rotated = true;
+                }
+            }
+        }
+        rotated
+    }
+
+    /// Given a guide and a proposed delta, returns a delta that moves the guide
+    /// onto a nearby target, if there is one, along with what it snapped to.
+    pub(crate) fn snap_guide_delta(&self, guide: &Guide, delta: DVec2) -> (DVec2, Snapped) {
+        let mut moved = guide.clone();
+        moved.nudge(delta);
+        let pos = moved.position();
+        let mut exclude = Selection::new();
+        exclude.insert(guide.id);
+
+        let mut snapped = self.snap_position(pos, &exclude);
+        match moved.guide {
+            GuideLine::Horiz(_) => {
+                snapped.point.x = pos.x;
+                snapped.x = None;
+            }
+            GuideLine::Vertical(_) => {
+                snapped.point.y = pos.y;
+                snapped.y = None;
+            }
+            GuideLine::Angle { .. } => (),
+        }
+        (delta + (snapped.point - pos), snapped)
+    }
+
+    /// Snap `pos` to the nearest enabled targets, independently on each axis.
+    ///
+    /// Targets are tried in the priority order of `self.snap`, and then the
+    /// grid, if grid snapping is on. Points and guides in `exclude` are not
+    /// targets, so that the items being dragged don't snap to themselves.
+    pub(crate) fn snap_position(&self, pos: DPoint, exclude: &Selection) -> Snapped {
+        let threshold = SNAP_DISTANCE / self.viewport.zoom;
+        let targets: Vec<_> = self
+            .snap
+            .priority
+            .iter()
+            .map(|kind| (*kind, self.snap_targets(*kind, exclude)))
+            .collect();
+
+        let x_targets = targets.iter().map(|(kind, (xs, _))| (*kind, xs.as_slice()));
+        let y_targets = targets.iter().map(|(kind, (_, ys))| (*kind, ys.as_slice()));
+        let x = snap::snap_axis(pos.x, x_targets, threshold);
+        let y = snap::snap_axis(pos.y, y_targets, threshold);
+
+        // angled guides have no fixed x or y, so we snap to one only if
+        // nothing else is in reach.
+        let snaps_to_guides = targets.iter().any(|(kind, _)| *kind == SnapKind::Guide);
+        if x.is_none() && y.is_none() && snaps_to_guides {
+            if let Some(point) = self.nearest_angled_guide(pos, threshold, exclude) {
+                return Snapped {
+                    point,
+                    x: Some(SnapKind::Guide),
+                    y: Some(SnapKind::Guide),
+                };
+            }
+        }
+        // the grid is always in reach, so it is the last resort on each axis
+        let grid = self.snap_to_grid(pos);
+        Snapped {
+            point: DPoint::from_raw((
+                x.map(|(x, _)| x).unwrap_or(grid.x),
+                y.map(|(y, _)| y).unwrap_or(grid.y),
+            )),
+            x: x.map(|(_, kind)| kind),
+            y: y.map(|(_, kind)| kind),
+        }
+    }
+
+    /// The nearest point on an angled guide within `max_dist` of `pos`.
+    fn nearest_angled_guide(
+        &self,
+        pos: DPoint,
+        max_dist: f64,
+        exclude: &Selection,
+    ) -> Option<DPoint> {
+        self.guides
+            .iter()
+            .filter(|g| !exclude.contains(&g.id))
+            .filter_map(|g| g.guide.nearest_on_angle(pos))
+            .map(|p| (p, (p - pos).hypot()))
+            .filter(|(_, dist)| *dist <= max_dist)
+            .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
+            .map(|(p, _)| p)
+    }
+
+    /// The x and y positions of targets of this kind.
+    fn snap_targets(&self, kind: SnapKind, exclude: &Selection) -> (Vec<f64>, Vec<f64>) {
+        let mut xs = Vec::new();
+        let mut ys = Vec::new();
+        match kind {
+            SnapKind::Point => {
+                for point in self.iter_points() {
+                    if point.is_on_curve() && !exclude.contains(&point.id) {
+                        xs.push(point.point.x);
+                        ys.push(point.point.y);
+                    }
+                }
+            }
+            SnapKind::Guide => {
+                for guide in self.guides.iter().filter(|g| !exclude.contains(&g.id)) {
+                    match guide.guide {
+                        GuideLine::Horiz(p) => ys.push(p.y),
+                        GuideLine::Vertical(p) => xs.push(p.x),
+                        GuideLine::Angle { .. } => (),
+                    }
+                }
+            }
+            SnapKind::Metric => {
+                xs.push(0.0);
+                xs.extend(self.glyph.advance.as_ref().map(|a| a.width as f64));
+                ys = self.metrics.lines();
+            }
+        }
+        (xs, ys)
+    }
+
+    /// If `point` is an extremum near a metric line, returns that metric and
+    /// the point's signed distance beyond it.
+    ///
+    /// The distance is positive above the metric; points that are further
+    /// than `MAX_OVERSHOOT` of the em from every metric have no overshoot.
+    pub fn overshoot_of(&self, point: EntityId) -> Option<(Metric, f64)> {
+        let path = self.path_for_point(point)?;
+        if !path.is_vertical_extremum(point) {
+            return None;
+        }
+        let y = path.path_point_for_id(point)?.point.y;
+        let (metric, pos) = self.metrics.nearest_metric(y);
+        let overshoot = y - pos;
+        if overshoot.abs() <= self.metrics.units_per_em * MAX_OVERSHOOT {
+            Some((metric, overshoot))
+        } else {
+            None
+        }
+    }
+
+    /// The overshoot of the selected extrema, if they share a metric.
+    ///
+    /// If the extrema have different overshoots, this is their mean.
+    fn selection_overshoot(&self) -> Option<(Metric, f64)> {
+        let overshoots = self
+            .selection
+            .iter()
+            .flat_map(|id| self.overshoot_of(*id))
+            .collect::<Vec<_>>();
+        let metric = overshoots.first()?.0;
+        if overshoots.iter().any(|(m, _)| *m != metric) {
+            return None;
+        }
+        let total: f64 = overshoots.iter().map(|(_, dist)| dist).sum();
+        Some((metric, (total / overshoots.len() as f64).round()))
+    }
+
+    /// Move the selected extrema near `metric` vertically, so that each is
+    /// `value` units above it (or below, if `value` is negative).
+    ///
+    /// Handles move with their points; other selected points are unchanged.
+    pub fn set_selection_overshoot(&mut self, metric: Metric, value: f64) {
+        let target = match self.metrics.metric(metric) {
+            Some(pos) => pos + value,
+            None => return,
+        };
+        let to_move = self
+            .selection
+            .iter()
+            .filter(|id| matches!(self.overshoot_of(**id), Some((m, _)) if m == metric))
+            .flat_map(|id| self.path_point_for_id(*id))
+            .collect::<Vec<_>>();
+        for point in to_move {
+            let delta = DVec2::from_raw((0.0, target - point.point.y));
+            if let Some(path) = self.path_for_point_mut(point.id) {
+                path.nudge_points(&[point.id], delta);
+            }
+        }
+    }
+
     pub fn delete_selection(&mut self) {
         let to_delete = self.selection.per_path_selection();
         self.selection.clear();
@@ -366,6 +1069,11 @@ impl EditSession {
                 path.delete_points(path_points);
             } else if path_points[0].is_guide() {
                 self.guides_mut().retain(|g| !path_points.contains(&g.id));
+            } else if path_points[0].is_component() {
+                self.components_mut()
+                    .retain(|c| !path_points.contains(&c.id));
+            } else if path_points[0].is_anchor() {
+                self.anchors_mut().retain(|a| !path_points.contains(&a.id));
             }
         }
         self.paths_mut().retain(|p| !p.points().is_empty());
@@ -378,6 +1086,71 @@ impl EditSession {
         self.selection = self.iter_points().map(|p| p.id).collect();
     }
 
+    /// Select every point of this kind.
+    ///
+    /// If `extend` is true the points are added to the selection; otherwise
+    /// they replace it.
+    pub fn select_by_kind(&mut self, kind: PointKind, extend: bool) {
+        let points: Selection = self
+            .iter_points()
+            .filter(|p| kind.matches(p.typ))
+            .map(|p| p.id)
+            .collect();
+        self.selection = if extend {
+            self.selection.union(&points)
+        } else {
+            points
+        };
+    }
+
+    /// Select the points that are not selected, and deselect those that are.
+    ///
+    /// Off-curve points are included; selected guides stay selected.
+    pub fn invert_selection(&mut self) {
+        let points: Selection = self.iter_points().map(|p| p.id).collect();
+        self.selection = self.selection.symmetric_difference(&points);
+    }
+
+    /// The ids of the selected points and guides, in order.
+    pub fn selected_ids(&self) -> Vec<EntityId> {
+        self.selection.iter().copied().collect()
+    }
+
+    /// The selected points and their positions, in order.
+    ///
+    /// Selected guides are not included.
+    pub fn selected_points(&self) -> Vec<(EntityId, DPoint)> {
+        self.selection
+            .iter()
+            .filter_map(|id| self.path_point_for_id(*id))
+            .map(|p| (p.id, p.point))
+            .collect()
+    }
+
+    /// Replace the selection with these ids.
+    ///
+    /// Ids of points or guides that are not in this glyph are ignored.
+    pub fn set_selection(&mut self, ids: impl IntoIterator<Item = EntityId>) {
+        let selection = ids.into_iter().filter(|id| self.has_entity(*id)).collect();
+        self.selection = selection;
+    }
+
+    /// Returns `true` if this glyph has a point, guide, component or anchor
+    /// with this id; the advance is always there.
+    fn has_entity(&self, id: EntityId) -> bool {
+        if id.is_guide() {
+            self.guides.iter().any(|g| g.id == id)
+        } else if id.is_advance_width() {
+            true
+        } else if id.is_component() {
+            self.components.iter().any(|c| c.id == id)
+        } else if id.is_anchor() {
+            self.anchors.iter().any(|a| a.id == id)
+        } else {
+            self.path_point_for_id(id).is_some()
+        }
+    }
+
     /// returns a rect representing the containing rect of the current selection
     ///
     /// Will return Rect::ZERO if nothing is selected.
@@ -392,32 +1165,114 @@ impl EditSession {
         iter.fold(bbox, |bb, pt| bb.union_pt(pt))
     }
 
-    /// If the current selection is a single point, select the next point
-    /// on that path.
-    pub fn select_next(&mut self) {
-        if self.selection.len() != 1 {
-            return;
+    /// The design-space bounding box of the selected points, or `None` if
+    /// no points are selected.
+    pub fn selection_bounds(&self) -> Option<Rect> {
+        if self
+            .selection
+            .iter()
+            .any(|id| self.path_point_for_id(*id).is_some())
+        {
+            Some(self.selection_dpoint_bbox())
+        } else {
+            None
         }
-        let id = self.selection.iter().next().copied().unwrap();
-        let id = self
-            .path_for_point(id)
-            .map(|path| path.next_point(id).id)
-            .unwrap_or(id);
-        self.selection.select_one(id);
     }
 
-    /// If the current selection is a single point, select the previous point
-    /// on that path.
+    /// Select the next on-curve point along the contour of the selection,
+    /// skipping handles, and wrapping around at the end of the contour.
+    ///
+    /// If several points are selected, this selects the on-curve point after
+    /// the last of them on the first contour with a selection. The selection
+    /// does not record the order points were added in, so "last" is in the
+    /// contour's order. If no points are selected, this selects the start of
+    /// the first contour.
+    pub fn select_next(&mut self) {
+        self.select_adjacent_on_curve(true);
+    }
+
+    /// Like `select_next`, but goes backward, from the first selected point;
+    /// if no points are selected, this selects the end of the first contour.
     pub fn select_prev(&mut self) {
-        if self.selection.len() != 1 {
-            return;
+        self.select_adjacent_on_curve(false);
+    }
+
+    fn select_adjacent_on_curve(&mut self, forward: bool) {
+        let path = match self.first_selected_path_idx() {
+            Some(idx) => &self.paths[idx],
+            None => {
+                if let Some(path) = self.paths.first() {
+                    let start = path.start_point().id;
+                    let id = if forward {
+                        start
+                    } else {
+                        path.adjacent_on_curve(start, false)
+                    };
+                    self.selection.select_one(id);
+                }
+                return;
+            }
+        };
+
+        // closed paths store their start point last
+        let points = path.points();
+        let split = if path.is_closed() {
+            points.len() - 1
+        } else {
+            0
+        };
+        let mut selected = points[split..]
+            .iter()
+            .chain(&points[..split])
+            .filter(|p| self.selection.contains(&p.id));
+        let from = if forward {
+            selected.last()
+        } else {
+            selected.next()
+        };
+        if let Some(from) = from {
+            let id = path.adjacent_on_curve(from.id, forward);
+            self.selection.select_one(id);
+        }
+    }
+
+    /// Select the start point of the contour after the one containing the
+    /// selection, wrapping around at the end of the contour list.
+    ///
+    /// If nothing is selected, this selects the first contour.
+    pub fn select_next_contour(&mut self) {
+        let len = self.paths.len();
+        let idx = self
+            .first_selected_path_idx()
+            .map(|idx| (idx + 1) % len)
+            .unwrap_or(0);
+        self.select_contour_start(idx);
+    }
+
+    /// Select the start point of the contour before the one containing the
+    /// selection, wrapping around at the start of the contour list.
+    ///
+    /// If nothing is selected, this selects the last contour.
+    pub fn select_prev_contour(&mut self) {
+        let len = self.paths.len();
+        let idx = self
+            .first_selected_path_idx()
+            .map(|idx| (idx + len - 1) % len)
+            .unwrap_or_else(|| len.saturating_sub(1));
+        self.select_contour_start(idx);
+    }
+
+    fn first_selected_path_idx(&self) -> Option<usize> {
+        self.selection
+            .iter()
+            .find_map(|id| self.path_idx_for_point(*id))
+    }
+
+    fn select_contour_start(&mut self, idx: usize) {
+        if let Some(path) = self.paths.get(idx) {
+            let id = path.start_point().id;
+            self.selection.select_one(id);
         }
-        let id = self.selection.iter().next().copied().unwrap();
-        let id = self
-            .path_for_point(id)
-            .map(|path| path.prev_point(id).id)
-            .unwrap_or(id);
-        self.selection.select_one(id);
     }
 
     pub fn select_path(&mut self, point: Point, toggle: bool) -> bool {
@@ -444,17 +1299,48 @@ impl EditSession {
             return;
         }
 
+        let proportional = self.proportional_handles;
         let to_nudge = self.selection.per_path_selection();
         for path_points in to_nudge.iter() {
             if let Some(path) = self.path_for_point_mut(path_points[0]) {
-                path.nudge_points(path_points, nudge);
+                if proportional {
+                    path.nudge_points_proportionally(path_points, nudge);
+                } else {
+                    path.nudge_points(path_points, nudge);
+                }
             } else if path_points[0].is_guide() {
                 for id in path_points {
                     if let Some(guide) = self.guides_mut().iter_mut().find(|g| g.id == *id) {
                         guide.nudge(nudge);
                     }
                 }
+            } else if path_points[0].is_component() {
+                for component in self.components_mut() {
+                    if path_points.contains(&component.id) {
+                        component.nudge(nudge);
+                    }
+                }
+            } else if path_points[0].is_anchor() {
+                for anchor in self.anchors_mut() {
+                    if path_points.contains(&anchor.id) {
+                        anchor.nudge(nudge);
+                    }
+                }
+            } else if path_points[0].is_advance_width() {
+                self.set_advance_width(self.advance_width() + nudge.x);
+            }
+        }
+    }
+
+    /// The grid intersection nearest to `point`, if grid snapping is on;
+    /// otherwise `point` itself.
+    pub fn snap_to_grid(&self, point: DPoint) -> DPoint {
+        match self.grid {
+            Some(spacing) if spacing > 0. => {
+                let snap = |value: f64| (value / spacing).round() * spacing;
+                DPoint::from_raw((snap(point.x), snap(point.y)))
             }
+            _ => point,
         }
     }
 
@@ -467,6 +1353,28 @@ impl EditSession {
         }
     }
 
+    /// The glyph's advance width.
+    ///
+    /// If the glyph has no advance, this is the half-em width at which its
+    /// metrics are drawn.
+    pub fn advance_width(&self) -> f64 {
+        self.glyph
+            .advance
+            .as_ref()
+            .map(|a| a.width as f64)
+            .unwrap_or_else(|| (self.metrics.units_per_em * 0.5).round())
+    }
+
+    /// Set the glyph's advance width; it can't be less than zero.
+    pub fn set_advance_width(&mut self, width: f64) {
+        let width = width.round().max(0.0) as f32;
+        let glyph = Arc::make_mut(&mut self.glyph);
+        match glyph.advance.as_mut() {
+            Some(advance) => advance.width = width,
+            None => glyph.advance = Some(Advance { width, height: 0.0 }),
+        }
+    }
+
     pub(crate) fn adjust_sidebearing(&mut self, delta: f64, is_left: bool) {
         let glyph = Arc::make_mut(&mut self.glyph);
         if let Some(advance) = glyph.advance.as_mut() {
@@ -496,24 +1404,79 @@ impl EditSession {
         }
     }
 
+    /// Apply `affine`, in design space, to the selected points.
+    ///
+    /// The handles of selected on-curve points are transformed with them.
+    pub fn transform_selection(&mut self, affine: Affine) {
+        let sel = self.selection.per_path_selection();
+        for path_points in sel.iter() {
+            if let Some(path) = self.path_for_point_mut(path_points[0]) {
+                path.transform_points(path_points, affine, DPoint::ZERO);
+            }
+        }
+    }
+
+    /// Mirror the selected points across the center of their bounding box.
+    ///
+    /// Contours that are flipped entirely are also reversed, so that they
+    /// keep their direction. Returns `false`, doing nothing, if fewer than
+    /// two points are selected.
+    pub fn flip_selection(&mut self, axis: Axis) -> bool {
+        let selected_points = self
+            .selection
+            .iter()
+            .filter(|id| self.path_point_for_id(**id).is_some())
+            .count();
+        if selected_points < 2 {
+            return false;
+        }
+
+        let center = self.selection_dpoint_bbox().center().to_vec2();
+        let mirror = match axis {
+            Axis::Horizontal => Affine::scale_non_uniform(-1.0, 1.0),
+            Axis::Vertical => Affine::scale_non_uniform(1.0, -1.0),
+        };
+        self.transform_selection(Affine::translate(center) * mirror * Affine::translate(-center));
+
+        let selection = &self.selection;
+        for path in Arc::make_mut(&mut self.paths).iter_mut() {
+            let mut on_curves = path.points().iter().filter(|p| p.is_on_curve());
+            if on_curves.all(|p| selection.contains(&p.id)) {
+                path.reverse_contour();
+            }
+        }
+        true
+    }
+
     pub(crate) fn update_handle(&mut self, point: Point, is_locked: bool) {
-        let dpoint = self.viewport.from_screen(point);
+        let dpoint = self.snap_to_grid(self.viewport.from_screen(point));
         let id = *self.selection.iter().next().unwrap();
         if let Some(path) = self.path_for_point_mut(id) {
             path.update_handle(id, dpoint, is_locked);
         }
     }
 
-    pub(crate) fn align_selection(&mut self) {
+    /// Align the selected points on the center line of the narrower side of
+    /// their bounding box.
+    pub(crate) fn align_selection_centers(&mut self) {
         let bbox = self.selection_dpoint_bbox();
-        // TODO: is_empty() would be cleaner but hasn't landed yet
-        if bbox.area() == 0.0 {
-            return;
-        }
-        let (val, set_x) = if bbox.width() < bbox.height() {
-            (0.5 * (bbox.x0 + bbox.x1), true)
+        if bbox.width() < bbox.height() {
+            self.align_selection(AlignKind::HorizontalCenter);
         } else {
-            (0.5 * (bbox.y0 + bbox.y1), false)
+            self.align_selection(AlignKind::VerticalCenter);
+        }
+    }
+
+    /// Move the selected points to an edge or center of their bounding box.
+    pub fn align_selection(&mut self, kind: AlignKind) {
+        let bbox = self.selection_dpoint_bbox();
+        let (val, set_x) = match kind {
+            AlignKind::Left => (bbox.x0, true),
+            AlignKind::Right => (bbox.x1, true),
+            AlignKind::Top => (bbox.y1, false),
+            AlignKind::Bottom => (bbox.y0, false),
+            AlignKind::HorizontalCenter => (bbox.center().x, true),
+            AlignKind::VerticalCenter => (bbox.center().y, false),
         };
         let val = val.round();
         // make borrow checker happy; we could state-split the paths instead, but meh
@@ -525,7 +1488,69 @@ impl EditSession {
         }
     }
 
-    pub(crate) fn reverse_contours(&mut self) {
+    /// Move the selected points to `x` and `y`, in design space, leaving a
+    /// coordinate unchanged when it is `None`.
+    ///
+    /// A single selected point moves with its handles, as if nudged; if more
+    /// are selected, each has its coordinates set, which aligns them.
+    pub fn set_selection_position(&mut self, x: Option<f64>, y: Option<f64>) {
+        let points: Vec<_> = self
+            .selection
+            .iter()
+            .filter_map(|id| self.path_point_for_id(*id))
+            .collect();
+        if let [point] = points.as_slice() {
+            if self.selection.len() == 1 {
+                let pos = point.point;
+                let target = DPoint::from_raw((x.unwrap_or(pos.x), y.unwrap_or(pos.y)));
+                self.nudge_selection(target - pos);
+                return;
+            }
+        }
+        for point in points {
+            if let Some(path) = self.path_for_point_mut(point.id) {
+                if let Some(x) = x {
+                    path.align_point(point.id, x.round(), true);
+                }
+                if let Some(y) = y {
+                    path.align_point(point.id, y.round(), false);
+                }
+            }
+        }
+    }
+
+    /// Move the selection by `delta`, as the arrow keys do.
+    pub fn offset_selection(&mut self, delta: DVec2) {
+        self.nudge_selection(delta);
+    }
+
+    /// Space the selected points evenly along `axis`, between the two that
+    /// are furthest apart.
+    pub fn distribute_selection(&mut self, axis: Axis) {
+        let set_x = axis == Axis::Horizontal;
+        let mut points: Vec<(f64, EntityId)> = self
+            .selection
+            .iter()
+            .filter_map(|id| self.path_point_for_id(*id))
+            .map(|pt| (if set_x { pt.point.x } else { pt.point.y }, pt.id))
+            .collect();
+        if points.len() < 3 {
+            return;
+        }
+        points.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+        let first = points[0].0;
+        let step = (points[points.len() - 1].0 - first) / (points.len() - 1) as f64;
+        for (i, (_, id)) in points.into_iter().enumerate() {
+            let val = (first + step * i as f64).round();
+            if let Some(path) = self.path_for_point_mut(id) {
+                path.align_point(id, val, set_x);
+            }
+        }
+    }
+
+    /// Reverse the contours with selected points, or every contour if
+    /// nothing is selected.
+    pub fn reverse_selected_contours(&mut self) {
         let mut path_ixs = BTreeSet::new();
         for entity in self.selection.iter() {
             if let Some(path_ix) = self.path_idx_for_point(*entity) {
@@ -541,6 +1566,50 @@ impl EditSession {
         }
     }
 
+    /// Reverse the closed contours that run the wrong way.
+    ///
+    /// Outer contours should run counter-clockwise, the contours directly
+    /// inside them clockwise, and so on; a contour's depth is the number of
+    /// other closed contours that surround it. Returns `true` if any contour
+    /// was reversed.
+    pub fn correct_path_direction(&mut self) -> bool {
+        let to_reverse: Vec<_> = self
+            .paths
+            .iter()
+            .enumerate()
+            .filter(|(idx, path)| {
+                let area = path.bezier().area();
+                if !path.is_closed() || area.abs() < f64::EPSILON {
+                    return false;
+                }
+                let depth = self
+                    .paths
+                    .iter()
+                    .enumerate()
+                    .filter(|(other_idx, other)| other_idx != idx && other.encloses(path))
+                    .count();
+                (area > 0.0) != (depth % 2 == 0)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let paths = self.paths_mut();
+        for idx in &to_reverse {
+            paths[*idx].reverse_contour();
+        }
+        !to_reverse.is_empty()
+    }
+
+    /// Inset (negative `distance`) or outset (positive `distance`) all closed
+    /// contours along their normals.
+    ///
+    /// Large offsets may produce overlaps that need to be removed afterwards.
+    pub fn offset_outline(&mut self, distance: f64) {
+        for path in self.paths_mut().iter_mut() {
+            path.offset(distance);
+        }
+    }
+
     pub(crate) fn add_guide(&mut self, point: Point) {
         // if one or two points are selected, use them. else use argument point.
         let guide = match self.selection.len() {
@@ -574,25 +1643,119 @@ impl EditSession {
         self.guides_mut().push(guide);
     }
 
+    /// Add a guide at an exact position, and select it.
+    pub fn add_guide_at(&mut self, spec: GuideSpec) {
+        let guide = Guide::from_spec(spec);
+        self.selection.select_one(guide.id);
+        self.guides_mut().push(guide);
+    }
+
+    /// Add a guide through `point`, at `degrees` counter-clockwise from
+    /// horizontal, and select it.
+    pub fn add_angled_guide(&mut self, point: DPoint, degrees: f64) -> EntityId {
+        let guide = Guide::from_spec(GuideSpec::Angle { point, degrees });
+        let id = guide.id;
+        self.selection.select_one(id);
+        self.guides_mut().push(guide);
+        id
+    }
+
+    /// Turn the guide with this `id` into a guide through `point` at
+    /// `degrees`.
+    pub fn set_guide_angle(&mut self, id: EntityId, point: DPoint, degrees: f64) {
+        if let Some(guide) = self.guides_mut().iter_mut().find(|g| g.id == id) {
+            guide.guide = GuideLine::through(point, degrees);
+        }
+    }
+
+    /// Add a component referencing `base`, placed by `transform`, and select
+    /// it.
+    pub fn add_component(&mut self, base: GlyphName, transform: Affine) -> EntityId {
+        let component = Component::new(base, transform);
+        let id = component.id;
+        self.components_mut().push(component);
+        self.selection.select_one(id);
+        id
+    }
+
+    /// Add an anchor, and select it.
+    pub fn add_anchor(&mut self, name: impl Into<String>, point: DPoint) -> EntityId {
+        let anchor = Anchor::new(name.into(), point);
+        let id = anchor.id;
+        self.anchors_mut().push(anchor);
+        self.selection.select_one(id);
+        id
+    }
+
+    /// Rename the anchor with this id, returning `false` if there is none.
+    pub fn rename_anchor(&mut self, id: EntityId, name: String) -> bool {
+        match self.anchors_mut().iter_mut().find(|a| a.id == id) {
+            Some(anchor) => {
+                anchor.name = name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the glyph's lib, if it has one.
+    pub fn lib(&self) -> Option<&Dictionary> {
+        self.glyph.lib.as_ref()
+    }
+
+    /// Set a value in the glyph's lib, creating the lib if necessary.
+    pub fn set_lib_value(&mut self, key: impl Into<String>, value: Value) {
+        let glyph = Arc::make_mut(&mut self.glyph);
+        glyph
+            .lib
+            .get_or_insert_with(Dictionary::new)
+            .insert(key.into(), value);
+    }
+
+    /// Remove a value from the glyph's lib, returning it if it existed.
+    ///
+    /// The lib itself is removed once it is empty.
+    pub fn remove_lib_value(&mut self, key: &str) -> Option<Value> {
+        self.glyph.lib.as_ref()?.get(key)?;
+        let glyph = Arc::make_mut(&mut self.glyph);
+        let lib = glyph.lib.as_mut()?;
+        let removed = lib.remove(key);
+        if lib.is_empty() {
+            glyph.lib = None;
+        }
+        removed
+    }
+
     /// Convert the current session back into a norad `Glyph`, for saving.
+    ///
+    /// Anything we don't edit here, such as the note and lib, is
+    /// carried over unchanged from the glyph we loaded.
     pub fn to_norad_glyph(&self) -> Glyph {
-        let mut glyph = Glyph::new_named("");
+        let mut glyph = (*self.glyph).clone();
         glyph.name = self.name.clone();
-        glyph.advance = self.glyph.advance.clone();
-        glyph.codepoints = self.glyph.codepoints.clone();
 
         let contours: Vec<_> = self.paths.iter().map(Path::to_norad).collect();
         let components: Vec<_> = self.components.iter().map(Component::to_norad).collect();
-        if !contours.is_empty() || !components.is_empty() {
-            glyph.outline = Some(Outline {
+        glyph.outline = if !contours.is_empty() || !components.is_empty() {
+            Some(Outline {
                 components,
                 contours,
-            });
-        }
+            })
+        } else {
+            None
+        };
         let guidelines: Vec<_> = self.guides.iter().map(Guide::to_norad).collect();
-        if !guidelines.is_empty() {
-            glyph.guidelines = Some(guidelines);
-        }
+        glyph.guidelines = if !guidelines.is_empty() {
+            Some(guidelines)
+        } else {
+            None
+        };
+        let anchors: Vec<_> = self.anchors.iter().map(Anchor::to_norad).collect();
+        glyph.anchors = if !anchors.is_empty() {
+            Some(anchors)
+        } else {
+            None
+        };
         glyph
     }
 }
@@ -607,6 +1770,105 @@ impl CoordinateSelection {
     pub const quadrant_bbox: lenses::QuadrantBbox = lenses::QuadrantBbox;
 }
 
+impl MasterEdit {
+    fn is_empty(&self) -> bool {
+        self.contours
+            .iter()
+            .flatten()
+            .all(|(_, offset)| *offset == Vec2::ZERO)
+    }
+
+    /// Move the corresponding points of `glyph`, returning `false` if its
+    /// outline is not compatible.
+    ///
+    /// Each point is matched by its identifier if it has one that is in
+    /// `glyph`, or else by its index; if this would match two points to the
+    /// same one, the outline is not compatible.
+    pub fn apply(&self, glyph: &mut Glyph) -> bool {
+        let contours = match glyph.outline.as_mut() {
+            Some(outline) => &mut outline.contours,
+            None => return false,
+        };
+        let compatible = contours.len() == self.contours.len()
+            && contours
+                .iter()
+                .zip(self.contours.iter())
+                .all(|(contour, offsets)| contour.points.len() == offsets.len());
+        if !compatible {
+            return false;
+        }
+
+        // the contour of each offset, and the point it moves
+        let mut moves = Vec::new();
+        for (contour_idx, offsets) in self.contours.iter().enumerate() {
+            for (point_idx, (identifier, offset)) in offsets.iter().enumerate() {
+                let target = identifier
+                    .as_ref()
+                    .and_then(|id| find_point(contours, id))
+                    .unwrap_or((contour_idx, point_idx));
+                moves.push((contour_idx, *offset, target));
+            }
+        }
+        let mut targets: Vec<_> = moves.iter().map(|(_, _, target)| *target).collect();
+        targets.sort_unstable();
+        targets.dedup();
+        if targets.len() != moves.len() {
+            return false;
+        }
+
+        let target_bounds: Vec<_> = contours.iter().map(point_bounds).collect();
+        for (contour_idx, offset, (target_contour, target_point)) in moves {
+            let scale = contour_scale(self.bounds[contour_idx], target_bounds[target_contour]);
+            let point = &mut contours[target_contour].points[target_point];
+            point.x += (offset.x * scale.x).round() as f32;
+            point.y += (offset.y * scale.y).round() as f32;
+        }
+        true
+    }
+}
+
+/// The bounds of a contour's points, including its off-curve points.
+fn point_bounds(contour: &Contour) -> Rect {
+    let mut points = contour
+        .points
+        .iter()
+        .map(|p| Point::new(p.x.into(), p.y.into()));
+    let first = points.next().unwrap_or_default();
+    points.fold(Rect::from_points(first, first), |rect, p| rect.union_pt(p))
+}
+
+/// How much to scale an offset made to a contour with bounds `from` to suit
+/// the same contour with bounds `to`, on each axis.
+///
+/// An axis where either contour is flat is not scaled.
+fn contour_scale(from: Rect, to: Rect) -> Vec2 {
+    let ratio = |from: f64, to: f64| {
+        if from < 1.0 || to < 1.0 {
+            1.0
+        } else {
+            to / from
+        }
+    };
+    Vec2::new(
+        ratio(from.width(), to.width()),
+        ratio(from.height(), to.height()),
+    )
+}
+
+/// The contour and point index of the point with this identifier.
+fn find_point(contours: &[Contour], identifier: &Identifier) -> Option<(usize, usize)> {
+    contours
+        .iter()
+        .enumerate()
+        .find_map(|(contour_idx, contour)| {
+            contour
+                .points
+                .iter()
+                .position(|p| p.identifier.as_ref() == Some(identifier))
+                .map(|point_idx| (contour_idx, point_idx))
+        })
+}
+
 pub mod lenses {
     use super::*;
     use druid::Lens;
@@ -618,10 +1880,12 @@ pub mod lenses {
             let count = data.selection.len();
             let frame = data.selection_dpoint_bbox();
             let quadrant = data.quadrant;
+            let overshoot = data.selection_overshoot();
             f(&CoordinateSelection {
                 count,
                 quadrant,
                 frame,
+                overshoot,
             })
         }
 
@@ -633,10 +1897,12 @@ pub mod lenses {
             let count = data.selection.len();
             let frame = data.selection_dpoint_bbox();
             let quadrant = data.quadrant;
+            let overshoot = data.selection_overshoot();
             let mut sel = CoordinateSelection {
                 count,
                 quadrant,
                 frame,
+                overshoot,
             };
             let r = f(&mut sel);
             data.quadrant = sel.quadrant;
@@ -692,3 +1958,559 @@ pub mod lenses {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use norad::Ufo;
+
+    /// A session editing `glyph`, in a font with no other glyphs.
+    fn session_with(glyph: Glyph) -> EditSession {
+        let name = glyph.name.clone();
+        let mut ufo = Ufo::new();
+        ufo.get_default_layer_mut().unwrap().insert_glyph(glyph);
+        let mut workspace = Workspace::default();
+        workspace.set_file(ufo, None);
+        EditSession::new(&name, &workspace)
+    }
+
+    #[test]
+    fn to_norad_glyph_keeps_unedited_data() {
+        let mut lib = Dictionary::new();
+        lib.insert("com.example.string".into(), Value::String("hello".into()));
+        lib.insert("com.example.number".into(), Value::Real(1.5));
+        let mut glyph = Glyph::new_named("a");
+        glyph.lib = Some(lib.clone());
+        glyph.note = Some("a note".into());
+
+        let mut session = session_with(glyph);
+        let saved = session.to_norad_glyph();
+        assert_eq!(saved.lib, Some(lib));
+        assert_eq!(saved.note, Some("a note".to_string()));
+
+        session.set_lib_value("com.example.string", Value::String("bye".into()));
+        assert!(session.remove_lib_value("com.example.number").is_some());
+        let saved = session.to_norad_glyph();
+        let saved_lib = saved.lib.unwrap();
+        assert_eq!(saved_lib.len(), 1);
+        assert_eq!(
+            saved_lib
+                .get("com.example.string")
+                .and_then(Value::as_string),
+            Some("bye")
+        );
+    }
+
+    #[test]
+    fn overshoot_of_extrema_and_set_overshoot() {
+        let mut session = session_with(Glyph::new_named("o"));
+        session.metrics.x_height = Some(500.0);
+
+        let mut path = Path::new(DPoint::new(250.0, -10.0));
+        let side = path.append_point(DPoint::new(500.0, 250.0));
+        let top = path.append_point(DPoint::new(250.0, 510.0));
+        path.append_point(DPoint::new(0.0, 250.0));
+        let bottom = path.close();
+        session.paths_mut().push(path);
+
+        assert_eq!(session.overshoot_of(top), Some((Metric::XHeight, 10.0)));
+        assert_eq!(
+            session.overshoot_of(bottom),
+            Some((Metric::Baseline, -10.0))
+        );
+        assert_eq!(session.overshoot_of(side), None);
+
+        session.selection = [top, bottom, side].iter().copied().collect();
+        session.set_selection_overshoot(Metric::XHeight, 12.0);
+        assert_eq!(session.overshoot_of(top), Some((Metric::XHeight, 12.0)));
+        assert_eq!(session.path_point_for_id(bottom).unwrap().point.y, -10.0);
+        assert_eq!(session.path_point_for_id(side).unwrap().point.y, 250.0);
+    }
+
+    #[test]
+    fn flip_selection_keeps_direction() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut path = Path::new(DPoint::new(0.0, 0.0));
+        let corner = path.append_point(DPoint::new(100.0, 0.0));
+        path.append_point(DPoint::new(0.0, 100.0));
+        path.close();
+        session.paths_mut().push(path);
+        let area = session.paths[0].bezier().area();
+
+        session.selection.select_one(corner);
+        assert!(!session.flip_selection(Axis::Horizontal));
+        session.select_all();
+        assert!(session.flip_selection(Axis::Horizontal));
+        assert_eq!(session.path_point_for_id(corner).unwrap().point.x, 0.0);
+        assert_eq!(session.paths[0].bezier().area(), area);
+    }
+
+    #[test]
+    fn paste_in_place_offsets_overlapping_paths() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut path = Path::new(DPoint::new(0.0, 0.0));
+        path.append_point(DPoint::new(100.0, 0.0));
+        session.paths_mut().push(path.clone());
+
+        session.paste(vec![path.duplicate()], PasteMode::InPlace);
+        session.paste(vec![path.duplicate()], PasteMode::InPlace);
+        let starts: Vec<_> = session.paths.iter().map(|p| p.points()[0].point).collect();
+        assert_eq!(
+            starts,
+            vec![
+                DPoint::new(0.0, 0.0),
+                DPoint::new(10.0, -10.0),
+                DPoint::new(20.0, -20.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn hit_test_reach_ignores_zoom() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut path = Path::new(DPoint::new(0.0, 0.0));
+        let end = path.append_point(DPoint::new(100.0, 0.0));
+        session.paths_mut().push(path);
+
+        for &zoom in &[0.1, 1.0, 8.0] {
+            session.viewport.zoom = zoom;
+            let point = session.viewport.to_screen(DPoint::new(100.0, 0.0));
+            assert_eq!(session.hit_test_all(point + (8.0, 0.0), None), Some(end));
+            assert_eq!(session.hit_test_all(point + (12.0, 0.0), None), None);
+
+            let middle = session.viewport.to_screen(DPoint::new(50.0, 0.0));
+            let hits_segment = |dy: f64| session.hit_test_segments(middle + (0.0, dy), None);
+            assert!(hits_segment(8.0).is_some());
+            assert!(hits_segment(12.0).is_none());
+        }
+    }
+
+    #[test]
+    fn angled_guides_snap_and_delete() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let id = session.add_angled_guide(DPoint::new(0., 0.), 45.0);
+        let snapped = session.snap_position(DPoint::new(300., 296.), &Selection::new());
+        assert_eq!(snapped.point, DPoint::new(298., 298.));
+        assert_eq!(snapped.x, Some(SnapKind::Guide));
+
+        let reloaded = Guide::from_norad(&session.guides[0].to_norad());
+        let nearest = reloaded.guide.nearest_on_angle(DPoint::new(300., 296.));
+        assert_eq!(nearest, Some(DPoint::new(298., 298.)));
+
+        assert!(session.selection.contains(&id));
+        session.delete_selection();
+        assert!(session.guides.is_empty());
+    }
+
+    #[test]
+    fn correct_direction_of_nested_contours() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        // both squares run clockwise
+        let square = |x0: f64, x1: f64| {
+            let mut path = Path::new(DPoint::new(x0, x0));
+            path.append_point(DPoint::new(x0, x1));
+            path.append_point(DPoint::new(x1, x1));
+            path.append_point(DPoint::new(x1, x0));
+            path.close();
+            path
+        };
+        session.paths_mut().push(square(0., 100.));
+        session.paths_mut().push(square(25., 75.));
+        assert!(session.paths[0].bezier().area() < 0.0);
+
+        assert!(session.correct_path_direction());
+        assert!(session.paths[0].bezier().area() > 0.0);
+        assert!(session.paths[1].bezier().area() < 0.0);
+        assert!(!session.correct_path_direction());
+
+        session.reverse_selected_contours();
+        assert!(session.paths[0].bezier().area() < 0.0);
+        assert!(session.paths[1].bezier().area() > 0.0);
+    }
+
+    #[test]
+    fn join_selection_merges_and_closes_contours() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        // two open paths, one ending where the other ends
+        let mut first = Path::new(DPoint::new(0., 0.));
+        let first_end = first.append_point(DPoint::new(100., 0.));
+        let mut second = Path::new(DPoint::new(100., 100.));
+        let second_end = second.append_point(DPoint::new(100., 0.));
+        *session.paths_mut() = vec![first, second];
+
+        session.selection.select_one(first_end);
+        assert!(!session.join_selection());
+        session.selection.insert(second_end);
+        assert!(session.join_selection());
+        assert_eq!(session.paths.len(), 1);
+        assert_eq!(session.paths[0].points().len(), 3);
+        assert!(!session.paths[0].is_closed());
+        assert_eq!(session.selected_ids(), vec![first_end]);
+
+        // joining the two ends of the result closes it with a line
+        let start = session.paths[0].points()[0].id;
+        let end = session.paths[0].points()[2].id;
+        session.set_selection(vec![start, end]);
+        assert!(session.join_selection());
+        assert!(session.paths[0].is_closed());
+        assert_eq!(session.paths[0].iter_segments().count(), 3);
+        assert!(!session.join_selection());
+    }
+
+    #[test]
+    fn anchors_are_edited_and_saved() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let id = session.add_anchor("top", DPoint::new(250., 700.));
+        let near = session.viewport.to_screen(DPoint::new(252., 701.));
+        assert_eq!(session.hit_test_all(near, None), Some(id));
+        assert!(session.rename_anchor(id, "_top".into()));
+        session.nudge_selection(DVec2::from_raw((10., -5.)));
+
+        let saved = session.to_norad_glyph();
+        let saved = saved.anchors.as_ref().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].name.as_deref(), Some("_top"));
+        assert_eq!((saved[0].x, saved[0].y), (260., 695.));
+
+        session.delete_selection();
+        assert!(session.anchors.is_empty());
+        assert!(session.to_norad_glyph().anchors.is_none());
+    }
+
+    #[test]
+    fn components_are_hit_moved_and_deleted() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let id = session.add_component("b".into(), Affine::translate((100., 0.)));
+        let square = Rect::new(0., 0., 50., 50.).to_path(0.1);
+        session.components_mut()[0].outline = Some(Arc::new(square));
+        let screen = |session: &EditSession, x, y| session.viewport.to_screen(DPoint::new(x, y));
+        assert_eq!(
+            session.hit_test_components(screen(&session, 125., 25.)),
+            Some(id)
+        );
+        assert_eq!(
+            session.hit_test_components(screen(&session, 25., 25.)),
+            None
+        );
+
+        session.nudge_selection(DVec2::from_raw((10., 0.)));
+        let bounds = session.components[0].bounding_box().unwrap();
+        assert_eq!(bounds, Rect::new(110., 0., 160., 50.));
+
+        session.delete_selection();
+        assert!(session.components.is_empty());
+    }
+
+    #[test]
+    fn advance_width_can_be_hit_and_nudged() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        session.set_advance_width(400.4);
+        assert_eq!(session.advance_width(), 400.);
+        let advance = EntityId::advance_width();
+        let on_marker = session.viewport.to_screen(DPoint::new(400., -4.));
+        assert_eq!(session.hit_test_all(on_marker, None), Some(advance));
+        let on_line = session.viewport.to_screen(DPoint::new(400., 250.));
+        assert_eq!(session.hit_test_all(on_line, None), None);
+
+        session.set_selection(Some(advance));
+        session.nudge_selection(DVec2::from_raw((-10., 5.)));
+        assert_eq!(session.advance_width(), 390.);
+        session.set_advance_width(-20.);
+        assert_eq!(session.advance_width(), 0.);
+    }
+
+    #[test]
+    fn snap_to_grid_only_when_enabled() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let point = DPoint::new(14., -16.);
+        assert_eq!(session.snap_to_grid(point), point);
+        session.grid = Some(10.);
+        assert_eq!(session.snap_to_grid(point), DPoint::new(10., -20.));
+        session.grid = Some(25.);
+        assert_eq!(session.snap_to_grid(point), DPoint::new(25., -25.));
+    }
+
+    #[test]
+    fn grid_snaps_axes_nothing_else_snapped() {
+        let mut session = session_with(Glyph::new_named("a"));
+        let guide = Guide::vertical(DPoint::new(103., 0.));
+        session.guides_mut().push(guide);
+        session.grid = Some(10.);
+
+        let snapped = session.snap_position(DPoint::new(101., 207.), &Selection::new());
+        assert_eq!(snapped.point, DPoint::new(103., 210.));
+        assert_eq!(snapped.x, Some(SnapKind::Guide));
+        assert_eq!(snapped.y, None);
+    }
+
+    #[test]
+    fn select_by_kind_filters_points() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (100., 50.), (100., 0.));
+        bez.line_to((200., 0.));
+        session.paths_mut().push(Path::from_bezpath(bez).unwrap());
+
+        session.select_by_kind(PointKind::OffCurve, false);
+        assert_eq!(session.selection.len(), 2);
+        session.select_by_kind(PointKind::Corner, false);
+        assert_eq!(session.selection.len(), 3);
+        session.select_by_kind(PointKind::OffCurve, true);
+        assert_eq!(session.selection.len(), 5);
+        session.select_by_kind(PointKind::Smooth, false);
+        assert!(session.selection.is_empty());
+    }
+
+    #[test]
+    fn set_selection_drops_stale_ids() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.line_to((100., 0.));
+        let path = Path::from_bezpath(bez.clone()).unwrap();
+        let stale = Path::from_bezpath(bez).unwrap().points()[0].id;
+        let ids: Vec<_> = path.points().iter().map(|p| p.id).collect();
+        session.paths_mut().push(path);
+        let guide = session.add_angled_guide(DPoint::new(50., 50.), 45.0);
+
+        session.set_selection(vec![ids[1], stale, guide]);
+        assert_eq!(session.selected_ids().len(), 2);
+        assert!(session.selection.contains(&guide));
+        assert_eq!(
+            session.selected_points(),
+            vec![(ids[1], DPoint::new(100., 0.))]
+        );
+    }
+
+    #[test]
+    fn select_next_skips_handles_and_wraps() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (100., 50.), (100., 0.));
+        bez.line_to((200., 0.));
+        session.paths_mut().push(Path::from_bezpath(bez).unwrap());
+        let selected = |session: &EditSession| {
+            assert_eq!(session.selection.len(), 1);
+            let id = session.selection.iter().next().unwrap();
+            session.path_point_for_id(*id).unwrap().point
+        };
+
+        session.select_next();
+        assert_eq!(selected(&session), DPoint::new(0., 0.));
+        session.select_next();
+        assert_eq!(selected(&session), DPoint::new(100., 0.));
+        session.select_next();
+        session.select_next();
+        assert_eq!(selected(&session), DPoint::new(0., 0.));
+        session.select_prev();
+        assert_eq!(selected(&session), DPoint::new(200., 0.));
+
+        session.select_all();
+        session.select_prev();
+        assert_eq!(selected(&session), DPoint::new(200., 0.));
+        session.select_all();
+        session.select_next();
+        assert_eq!(selected(&session), DPoint::new(0., 0.));
+    }
+
+    #[test]
+    fn hidden_handles_cant_be_hit() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (100., 50.), (100., 0.));
+        session.paths_mut().push(Path::from_bezpath(bez).unwrap());
+        let handle = session.viewport.to_screen(DPoint::new(0., 50.));
+        assert!(session.hit_test_all(handle, None).is_some());
+        session.hide_handles = true;
+        assert!(session.hit_test_all(handle, None).is_none());
+    }
+
+    #[test]
+    fn set_selection_position_moves_or_aligns() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (100., 50.), (100., 0.));
+        session.paths_mut().push(Path::from_bezpath(bez).unwrap());
+        let start = session.paths[0].start_point().id;
+        let points = |session: &EditSession| -> Vec<_> {
+            session.paths[0].points().iter().map(|p| p.point).collect()
+        };
+
+        // a single point brings its handle along
+        session.selection.select_one(start);
+        session.set_selection_position(Some(10.), None);
+        assert_eq!(
+            points(&session)[..2],
+            [DPoint::new(10., 0.), DPoint::new(10., 50.)]
+        );
+
+        session.select_all();
+        session.set_selection_position(None, Some(20.));
+        assert!(points(&session).iter().all(|p| p.y == 20.));
+        session.offset_selection(DVec2::from_raw((5., 0.)));
+        assert_eq!(points(&session)[3], DPoint::new(105., 20.));
+    }
+
+    #[test]
+    fn align_and_distribute_selection() {
+        let mut session = session_with(Glyph::new_named("a"));
+
+        let mut path = Path::new(DPoint::new(0.0, 0.0));
+        let middle = path.append_point(DPoint::new(40.0, 10.0));
+        let last = path.append_point(DPoint::new(100.0, 30.0));
+        session.paths_mut().push(path);
+        session.select_all();
+
+        session.distribute_selection(Axis::Horizontal);
+        assert_eq!(session.path_point_for_id(middle).unwrap().point.x, 50.0);
+        session.align_selection(AlignKind::Top);
+        let ys: Vec<_> = session.iter_points().map(|p| p.point.y).collect();
+        assert_eq!(ys, vec![30.0, 30.0, 30.0]);
+        session.align_selection(AlignKind::Left);
+        assert_eq!(session.path_point_for_id(last).unwrap().point.x, 0.0);
+    }
+
+    #[test]
+    fn point_identifiers_round_trip() {
+        use norad::glyph::{Contour, ContourPoint, PointType};
+        let point = |x, y, identifier: Option<&str>| ContourPoint {
+            name: None,
+            x,
+            y,
+            typ: PointType::Line,
+            smooth: false,
+            identifier: identifier.map(|s| Identifier::new(s).unwrap()),
+        };
+        let contour = Contour {
+            points: vec![
+                point(0.0, 0.0, Some("start")),
+                point(100.0, 0.0, None),
+                point(100.0, 100.0, Some("corner")),
+            ],
+            identifier: None,
+        };
+        let mut glyph = Glyph::new_named("a");
+        glyph.outline = Some(Outline {
+            contours: vec![contour],
+            components: Vec::new(),
+        });
+
+        let mut session = session_with(glyph);
+        let saved = session.to_norad_glyph();
+        let identifiers = |glyph: &Glyph| -> Vec<Option<String>> {
+            glyph.outline.as_ref().unwrap().contours[0]
+                .points
+                .iter()
+                .map(|p| p.identifier.as_ref().map(|id| id.as_str().to_string()))
+                .collect()
+        };
+        assert_eq!(
+            identifiers(&saved),
+            vec![Some("start".into()), None, Some("corner".into())]
+        );
+
+        let points: Vec<_> = session.paths[0].points().iter().map(|p| p.id).collect();
+        let unnamed = points
+            .iter()
+            .copied()
+            .find(|id| session.point_identifier(*id).is_none())
+            .unwrap();
+        let taken = Identifier::new("corner").unwrap();
+        assert!(!session.set_point_identifier(unnamed, Some(taken)));
+        let fresh = Identifier::new("middle").unwrap();
+        assert!(session.set_point_identifier(unnamed, Some(fresh)));
+        assert_eq!(
+            identifiers(&session.to_norad_glyph()),
+            vec![
+                Some("start".into()),
+                Some("middle".into()),
+                Some("corner".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn snapping_to_fractional_metrics() {
+        let mut session = session_with(Glyph::new_named("a"));
+        session.metrics.descender = Some(-212.5);
+        Arc::make_mut(&mut session.glyph).advance = Some(norad::glyph::Advance {
+            width: 500.5,
+            height: 0.0,
+        });
+
+        let snapped = session.snap_position(DPoint::new(498., -210.), &Selection::new());
+        assert_eq!(snapped.x, Some(SnapKind::Metric));
+        assert_eq!(snapped.y, Some(SnapKind::Metric));
+        assert_eq!(snapped.point, DPoint::new(501., -213.));
+    }
+
+    #[test]
+    fn repeat_edit_in_another_master() {
+        use norad::glyph::{Contour, ContourPoint, PointType};
+        let contour = |points: &[(f32, f32)]| Contour {
+            points: points
+                .iter()
+                .map(|&(x, y)| ContourPoint {
+                    name: None,
+                    x,
+                    y,
+                    typ: PointType::Line,
+                    smooth: false,
+                    identifier: None,
+                })
+                .collect(),
+            identifier: None,
+        };
+        let glyph_with = |points: &[(f32, f32)]| {
+            let mut glyph = Glyph::new_named("a");
+            glyph.outline = Some(Outline {
+                contours: vec![contour(points)],
+                components: Vec::new(),
+            });
+            glyph
+        };
+
+        let light = glyph_with(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0)]);
+        let mut session = session_with(light);
+        let before = session.clone();
+        assert!(session.edit_since(&before).is_none());
+
+        let corner = session
+            .iter_points()
+            .find(|p| p.point == DPoint::new(100.0, 100.0))
+            .unwrap()
+            .id;
+        session.selection.select_one(corner);
+        session.nudge_selection(DVec2::from_raw((10.0, -5.0)));
+        let edit = session.edit_since(&before).unwrap();
+
+        let mut bold = glyph_with(&[(0.0, 0.0), (200.0, 0.0), (200.0, 100.0)]);
+        assert!(edit.apply(&mut bold));
+        let points = &bold.outline.as_ref().unwrap().contours[0].points;
+        // the bold contour is twice as wide, so the point moves twice as far
+        assert_eq!((points[2].x, points[2].y), (220.0, 95.0));
+        assert_eq!((points[1].x, points[1].y), (200.0, 0.0));
+
+        let mut incompatible = glyph_with(&[(0.0, 0.0), (200.0, 0.0)]);
+        assert!(!edit.apply(&mut incompatible));
+    }
+}
@@ -0,0 +1,197 @@
+//! Exporting glyphs as images.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use druid::kurbo::{Rect, Vec2};
+use druid::piet::{Color, Device, ImageFormat, RenderContext};
+use druid::{Data, Lens};
+use norad::GlyphName;
+
+use crate::data::{FontMetrics, Workspace};
+use crate::design_space::ViewPort;
+use crate::draw;
+use crate::edit_session::EditSession;
+
+/// The default size of an exported image, in pixels per em.
+pub const DEFAULT_PNG_SIZE: f64 = 256.0;
+
+/// Options for exporting a glyph as a PNG.
+#[derive(Debug, Clone, Data, Lens)]
+pub struct PngOptions {
+    /// The size of the em, in pixels.
+    pub size: f64,
+    /// Whether to draw the glyph's metrics behind the outline.
+    pub show_metrics: bool,
+    /// Whether to draw a white background; otherwise it is transparent.
+    pub background: bool,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        PngOptions {
+            size: DEFAULT_PNG_SIZE,
+            show_metrics: false,
+            background: true,
+        }
+    }
+}
+
+/// Characters that can't be used in file names on some systems.
+const ILLEGAL_FILE_CHARS: &str = "\"*+/:<>?[\\]|";
+
+/// Names that can't be used as file names on Windows, whatever the extension.
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "con", "prn", "aux", "clock$", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7",
+    "com8", "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// The path we export a glyph image to, which is next to the font file, or
+/// in the current directory if the font has not been saved.
+///
+/// The file is named after the glyph in the way the UFO spec names glyph
+/// files, so that glyphs whose names differ only in case get different files;
+/// if that file exists, a number is added rather than replacing it.
+pub fn png_path(font: &Workspace, name: &GlyphName) -> PathBuf {
+    let dir = font
+        .font
+        .path
+        .as_ref()
+        .and_then(|path| path.parent())
+        .map(Path::to_owned)
+        .unwrap_or_default();
+    let stem = file_stem(name);
+    let mut path = dir.join(format!("{}.png", stem));
+    let mut count = 1;
+    while path.exists() {
+        count += 1;
+        path = dir.join(format!("{}-{}.png", stem, count));
+    }
+    path
+}
+
+/// A file name for this glyph name, following the UFO 3 conventions: an
+/// underscore follows each capital letter, and characters that aren't
+/// allowed in file names are replaced.
+fn file_stem(name: &str) -> String {
+    let mut stem = String::with_capacity(name.len());
+    for (i, c) in name.chars().enumerate() {
+        match c {
+            '.' if i == 0 => stem.push('_'),
+            c if ILLEGAL_FILE_CHARS.contains(c) || c.is_control() => stem.push('_'),
+            c if c.is_uppercase() => {
+                stem.push(c);
+                stem.push('_');
+            }
+            c => stem.push(c),
+        }
+    }
+    stem.split('.')
+        .map(|part| {
+            if RESERVED_FILE_NAMES.contains(&part.to_lowercase().as_str()) {
+                format!("_{}", part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Render the glyph in `session`, including its components, and write it to
+/// `path` as a PNG.
+///
+/// The image spans the glyph's advance horizontally and the font's
+/// descender to ascender vertically.
+pub fn export_png(
+    session: &EditSession,
+    font: &Workspace,
+    metrics: &FontMetrics,
+    options: &PngOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let upm = metrics.units_per_em;
+    let ascender = metrics.ascender.unwrap_or_else(|| (upm * 0.8).round());
+    let descender = metrics.descender.unwrap_or_else(|| -(upm * 0.2).round());
+    let advance = session
+        .glyph
+        .advance
+        .as_ref()
+        .map(|a| a.width as f64)
+        .unwrap_or_else(|| (upm * 0.5).round());
+
+    let scale = options.size / upm;
+    let width = (advance * scale).ceil().max(1.0) as usize;
+    let height = ((ascender - descender) * scale).ceil().max(1.0) as usize;
+
+    let mut space = ViewPort::default();
+    space.zoom = scale;
+    space.set_offset(Vec2::new(0.0, ascender));
+    let visible_rect = Rect::new(0.0, 0.0, width as f64, height as f64);
+
+    let mut device = Device::new()?;
+    let mut target = device.bitmap_target(width, height, 1.0)?;
+    {
+        let mut rc = target.render_context();
+        let background = if options.background {
+            Color::WHITE
+        } else {
+            Color::rgba8(0, 0, 0, 0)
+        };
+        rc.clear(background);
+        draw::draw_glyph_image(
+            &mut rc,
+            space,
+            visible_rect,
+            metrics,
+            session,
+            font,
+            options.show_metrics,
+        );
+        rc.finish()?;
+    }
+
+    let mut pixels = target.into_raw_pixels(ImageFormat::RgbaPremul)?;
+    unpremultiply(&mut pixels);
+    write_png(path, width as u32, height as u32, &pixels)
+}
+
+/// Convert premultiplied RGBA pixels to the straight alpha PNG expects.
+fn unpremultiply(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        if alpha != 0 && alpha != 255 {
+            for channel in &mut pixel[..3] {
+                *channel = ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+            }
+        }
+    }
+}
+
+fn write_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), Box<dyn Error>> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::RGBA);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgba)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_stems_differ_by_case() {
+        assert_eq!(file_stem("a"), "a");
+        assert_eq!(file_stem("A"), "A_");
+        assert_eq!(file_stem("A.alt"), "A_.alt");
+        assert_eq!(file_stem(".notdef"), "_notdef");
+        assert_eq!(file_stem("a/b"), "a_b");
+        assert_eq!(file_stem("con"), "_con");
+        assert_eq!(file_stem("a.com1"), "a._com1");
+    }
+}
@@ -0,0 +1,137 @@
+//! Comparing a glyph's outline with its saved version.
+
+use std::sync::Arc;
+
+use druid::kurbo::BezPath;
+use druid::Data;
+use norad::glyph::{Contour, ContourPoint};
+
+use crate::design_space::DPoint;
+
+/// How a glyph's outline differs from its saved version.
+#[derive(Debug, Clone, Default, Data)]
+pub struct GlyphDiff {
+    /// The saved outline.
+    pub saved: Arc<BezPath>,
+    /// Points in the current outline but not in the saved one.
+    pub added: Arc<Vec<DPoint>>,
+    /// Points in the saved outline but not in the current one.
+    pub removed: Arc<Vec<DPoint>>,
+    /// Points that have moved, from their saved to their current position.
+    pub moved: Arc<Vec<(DPoint, DPoint)>>,
+}
+
+impl GlyphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+/// Compare two outlines, contour by contour.
+///
+/// Points with the same identifier correspond. Otherwise, if a contour has
+/// the same number of points as before, points correspond by their order;
+/// if it does not, only points that have not moved can be matched up, and
+/// the rest are reported as added or removed.
+pub(crate) fn diff_contours(saved: &[Contour], current: &[Contour], outline: BezPath) -> GlyphDiff {
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut moved = Vec::new();
+
+    for idx in 0..saved.len().max(current.len()) {
+        let old = saved.get(idx).map(|c| c.points.as_slice()).unwrap_or(&[]);
+        let new = current.get(idx).map(|c| c.points.as_slice()).unwrap_or(&[]);
+        let mut old_matched = vec![false; old.len()];
+
+        for (new_idx, new_pt) in new.iter().enumerate() {
+            let by_identifier = new_pt
+                .identifier
+                .as_ref()
+                .and_then(|id| old.iter().position(|p| p.identifier.as_ref() == Some(id)));
+            let by_order = if old.len() == new.len() {
+                Some(new_idx)
+            } else {
+                old.iter()
+                    .enumerate()
+                    .position(|(i, p)| !old_matched[i] && position(p) == position(new_pt))
+            };
+            match by_identifier.or(by_order).filter(|i| !old_matched[*i]) {
+                Some(old_idx) => {
+                    old_matched[old_idx] = true;
+                    let (from, to) = (position(&old[old_idx]), position(new_pt));
+                    if from != to {
+                        moved.push((from, to));
+                    }
+                }
+                None => added.push(position(new_pt)),
+            }
+        }
+        removed.extend(
+            old.iter()
+                .zip(old_matched)
+                .filter(|(_, matched)| !matched)
+                .map(|(p, _)| position(p)),
+        );
+    }
+
+    GlyphDiff {
+        saved: Arc::new(outline),
+        added: Arc::new(added),
+        removed: Arc::new(removed),
+        moved: Arc::new(moved),
+    }
+}
+
+fn position(point: &ContourPoint) -> DPoint {
+    DPoint::new(point.x.round().into(), point.y.round().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use norad::glyph::PointType;
+
+    fn contour(points: &[(f32, f32)]) -> Contour {
+        Contour {
+            points: points
+                .iter()
+                .map(|&(x, y)| ContourPoint {
+                    name: None,
+                    x,
+                    y,
+                    typ: PointType::Line,
+                    smooth: false,
+                    identifier: None,
+                })
+                .collect(),
+            identifier: None,
+        }
+    }
+
+    #[test]
+    fn diff_moved_added_and_removed() {
+        let saved = [
+            contour(&[(0., 0.), (100., 0.), (100., 100.)]),
+            contour(&[(200., 0.), (300., 0.), (300., 100.)]),
+        ];
+        let current = [
+            contour(&[(0., 0.), (110., 0.), (100., 100.)]),
+            contour(&[(200., 0.), (300., 0.), (300., 100.), (200., 100.)]),
+            contour(&[(500., 500.)]),
+        ];
+        let diff = diff_contours(&saved, &current, BezPath::new());
+        assert_eq!(
+            *diff.moved,
+            vec![(DPoint::new(100., 0.), DPoint::new(110., 0.))]
+        );
+        assert_eq!(
+            *diff.added,
+            vec![DPoint::new(200., 100.), DPoint::new(500., 500.)]
+        );
+        assert!(diff.removed.is_empty());
+
+        let diff = diff_contours(&current, &saved, BezPath::new());
+        assert_eq!(diff.removed.len(), 2);
+        assert!(diff_contours(&saved, &saved, BezPath::new()).is_empty());
+    }
+}
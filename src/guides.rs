@@ -1,9 +1,16 @@
+use std::fmt;
+use std::str::FromStr;
+
 use druid::kurbo::{Line, ParamCurveNearest, Point, Vec2};
 use druid::Data;
 
 use crate::design_space::{DPoint, DVec2, ViewPort};
 use crate::path::EntityId;
 
+/// The distance between the two points that define an angled guide created
+/// from an angle; larger values make the rounded angle more precise.
+const ANGLE_GUIDE_LENGTH: f64 = 1000.0;
+
 #[derive(Debug, Clone, Data)]
 pub struct Guide {
     pub id: EntityId,
@@ -18,6 +25,17 @@ pub enum GuideLine {
     Angle { p1: DPoint, p2: DPoint },
 }
 
+/// A description of a guide at an exact position, as typed by the user.
+#[derive(Debug, Clone, Copy, Data, PartialEq)]
+pub enum GuideSpec {
+    /// A horizontal guide at this y position.
+    Horiz(f64),
+    /// A vertical guide at this x position.
+    Vertical(f64),
+    /// A guide through `point`, at `degrees` counter-clockwise from horizontal.
+    Angle { point: DPoint, degrees: f64 },
+}
+
 impl Guide {
     fn new(guide: GuideLine) -> Self {
         let id = EntityId::new_for_guide();
@@ -36,6 +54,15 @@ impl Guide {
         Guide::new(GuideLine::Angle { p1, p2 })
     }
 
+    /// Create a new guide from a `GuideSpec`.
+    pub fn from_spec(spec: GuideSpec) -> Self {
+        match spec {
+            GuideSpec::Horiz(y) => Guide::horiz(DPoint::from_raw((0., y))),
+            GuideSpec::Vertical(x) => Guide::vertical(DPoint::from_raw((x, 0.))),
+            GuideSpec::Angle { point, degrees } => Guide::new(GuideLine::through(point, degrees)),
+        }
+    }
+
     pub fn toggle_vertical_horiz(&mut self, new_point: DPoint) {
         let new = match self.guide {
             GuideLine::Horiz(_) => GuideLine::Vertical(new_point),
@@ -45,6 +72,16 @@ impl Guide {
         self.guide = new;
     }
 
+    /// The point that defines the guide's position.
+    ///
+    /// For horizontal and vertical guides only one coordinate is meaningful.
+    pub fn position(&self) -> DPoint {
+        match self.guide {
+            GuideLine::Horiz(p) | GuideLine::Vertical(p) => p,
+            GuideLine::Angle { p1, .. } => p1,
+        }
+    }
+
     pub fn screen_dist(&self, vport: ViewPort, point: Point) -> f64 {
         match self.guide {
             GuideLine::Horiz(p) => {
@@ -93,15 +130,11 @@ impl Guide {
             Line::Vertical(x) => GuideLine::Vertical(DPoint::new(x as f64, 0.)),
             Line::Horizontal(y) => GuideLine::Horiz(DPoint::new(0., y as f64)),
             Line::Angle { x, y, degrees } => {
-                let p1 = DPoint::new(x as f64, y as f64);
-                let p2 = p1.to_raw() + Vec2::from_angle(degrees as f64);
-                let p2 = DPoint::new(p2.x, p2.y);
-                GuideLine::Angle { p1, p2 }
+                GuideLine::through(DPoint::from_raw((x as f64, y as f64)), degrees as f64)
             }
         };
 
-        let id = EntityId::new_with_parent(0);
-        Guide { guide, id }
+        Guide::new(guide)
     }
 
     pub fn to_norad(&self) -> norad::Guideline {
@@ -112,7 +145,7 @@ impl Guide {
                 let x = p1.x as f32;
                 let y = p1.y as f32;
                 let angle = p2 - p1;
-                let degrees = angle.to_raw().atan2() as f32;
+                let degrees = angle.to_raw().atan2().to_degrees().rem_euclid(360.0) as f32;
                 norad::Line::Angle { x, y, degrees }
             }
         };
@@ -125,3 +158,78 @@ impl Guide {
         }
     }
 }
+
+impl GuideLine {
+    /// An angled line through `point`, at `degrees` counter-clockwise from
+    /// horizontal.
+    pub fn through(point: DPoint, degrees: f64) -> GuideLine {
+        let direction = Vec2::from_angle(degrees.to_radians()) * ANGLE_GUIDE_LENGTH;
+        let p2 = DPoint::from_raw(point.to_raw() + direction);
+        GuideLine::Angle { p1: point, p2 }
+    }
+
+    /// The point on an angled line nearest to `point`, in design space.
+    ///
+    /// Horizontal and vertical lines return `None`; they are snapped to one
+    /// axis at a time.
+    pub(crate) fn nearest_on_angle(&self, point: DPoint) -> Option<DPoint> {
+        match *self {
+            GuideLine::Angle { p1, p2 } => {
+                let dir = (p2 - p1).to_raw();
+                if dir.hypot() == 0.0 {
+                    return None;
+                }
+                let dir = dir.normalize();
+                let along = (point - p1).to_raw().dot(dir);
+                Some(DPoint::from_raw(p1.to_raw() + dir * along))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GuideSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuideSpec::Horiz(y) => write!(f, "y {}", y),
+            GuideSpec::Vertical(x) => write!(f, "x {}", x),
+            GuideSpec::Angle { point, degrees } => {
+                write!(f, "{}, {}, {}", point.x, point.y, degrees)
+            }
+        }
+    }
+}
+
+/// Parses `"x 100"` as a vertical guide, `"y 500"` as a horizontal guide,
+/// and `"100, 500, 45"` as a guide through a point at an angle in degrees.
+impl FromStr for GuideSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<GuideSpec, String> {
+        let s = s.trim();
+        let parse_num = |s: &str| {
+            s.trim()
+                .parse::<f64>()
+                .map_err(|e| format!("bad number '{}': {}", s.trim(), e))
+        };
+
+        if let Some(rest) = s.strip_prefix('x').or_else(|| s.strip_prefix('X')) {
+            return parse_num(rest).map(GuideSpec::Vertical);
+        }
+        if let Some(rest) = s.strip_prefix('y').or_else(|| s.strip_prefix('Y')) {
+            return parse_num(rest).map(GuideSpec::Horiz);
+        }
+
+        let parts = s.split(',').map(parse_num).collect::<Result<Vec<_>, _>>()?;
+        match parts.as_slice() {
+            [x, y, degrees] => Ok(GuideSpec::Angle {
+                point: DPoint::from_raw((*x, *y)),
+                degrees: *degrees,
+            }),
+            _ => Err(format!(
+                "expected 'x 100', 'y 100' or 'x, y, angle': '{}'",
+                s
+            )),
+        }
+    }
+}
@@ -8,6 +8,7 @@ extern crate serde_derive;
 #[macro_use]
 extern crate lopdf;
 
+mod anchor;
 mod app_delegate;
 mod bez_cache;
 mod clipboard;
@@ -15,16 +16,23 @@ mod component;
 mod consts;
 mod data;
 mod design_space;
+mod designspace;
 mod draw;
 mod edit_session;
+mod export;
+mod glyph_diff;
 mod glyph_names;
 mod guides;
 mod menus;
+mod metrics_keys;
 mod mouse;
 mod path;
 mod plist;
 mod quadrant;
+mod raster;
 mod selection;
+mod settings;
+mod snap;
 mod theme;
 mod tools;
 mod undo;
@@ -51,7 +59,10 @@ fn main() {
 
     AppLauncher::with_window(main_window)
         .delegate(app_delegate::Delegate::default())
-        .configure_env(|env, _| theme::configure_env(env))
+        .configure_env(|env, _| {
+            theme::configure_env(env);
+            settings::configure_env(env);
+        })
         .use_simple_logger()
         .launch(state)
         .expect("launch failed");
@@ -74,12 +85,19 @@ fn make_ui() -> impl Widget<AppState> {
         ctx.submit_command(cmd);
     });
 
+    let metrics_keys_button = Button::new("(check metrics keys)").on_click(|ctx, _data, _env| {
+        let cmd = ModalHost::make_modal_command(crate::widgets::metrics_key_checker);
+        ctx.submit_command(cmd);
+    });
+
     let main_view = Flex::column()
         .with_child(
             Flex::row()
                 .with_child(label)
                 .with_spacer(8.0)
                 .with_child(button)
+                .with_spacer(8.0)
+                .with_child(metrics_keys_button)
                 .padding(5.0)
                 .center()
                 .fix_height(40.)
@@ -99,9 +117,19 @@ fn make_ui() -> impl Widget<AppState> {
 }
 
 /// If there was an argument passed at the command line, try to open it as a .ufo
-/// file, otherwise return blank state.
+/// or .designspace file, otherwise return blank state.
 fn get_initial_state() -> AppState {
-    let (font_file, path) = if let Some(arg) = std::env::args().nth(1) {
+    let arg = std::env::args().nth(1);
+    if let Some(arg) = arg.as_ref().filter(|arg| arg.ends_with(".designspace")) {
+        let mut workspace = Workspace::default();
+        if let Err(e) = workspace.open_designspace(std::path::Path::new(arg)) {
+            eprintln!("Failed to load designspace '{}'.\nError:'{}'", arg, e);
+            std::process::exit(1);
+        }
+        return AppState { workspace };
+    }
+
+    let (font_file, path) = if let Some(arg) = arg {
         match norad::Ufo::load(&arg) {
             Ok(ufo) => (ufo, Some(std::path::PathBuf::from(arg))),
             Err(e) => {
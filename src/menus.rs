@@ -8,8 +8,13 @@ use druid::{
 
 use crate::consts;
 use crate::data::{AppState, EditorState};
+use crate::edit_session::{AlignKind, Axis};
 
 pub const UFO_FILE_TYPE: FileSpec = FileSpec::new("Font Object", &["ufo"]);
+pub const DESIGNSPACE_FILE_TYPE: FileSpec = FileSpec::new("Designspace", &["designspace"]);
+
+/// The distance, in design units, used by the inset/outset outline items.
+const OUTLINE_OFFSET_STEP: f64 = 10.0;
 
 /// Context menu's inner menu must have type T == the root app state.
 pub fn make_context_menu(data: &EditorState, pos: Point) -> MenuDesc<AppState> {
@@ -17,6 +22,15 @@ pub fn make_context_menu(data: &EditorState, pos: Point) -> MenuDesc<AppState> {
         LocalizedString::new("menu-item-add-guide").with_placeholder("Add Guide"),
         consts::cmd::ADD_GUIDE.with(pos),
     ));
+    menu = menu.append(MenuItem::new(
+        LocalizedString::new("menu-item-add-guide-at").with_placeholder("Add Guide At…"),
+        consts::cmd::SHOW_GUIDE_PROMPT,
+    ));
+
+    menu = menu.append(MenuItem::new(
+        LocalizedString::new("menu-item-add-anchor").with_placeholder("Add Anchor…"),
+        consts::cmd::SHOW_ANCHOR_PROMPT.with(consts::cmd::AnchorTarget::New(pos)),
+    ));
 
     // only show 'toggle guide' if a guide is selected
     if data.session.selection.len() == 1 && data.session.selection.iter().all(|s| s.is_guide()) {
@@ -28,6 +42,14 @@ pub fn make_context_menu(data: &EditorState, pos: Point) -> MenuDesc<AppState> {
             consts::cmd::TOGGLE_GUIDE.with(args),
         ));
     }
+
+    if data.session.selection.len() == 1 && data.session.selection.iter().all(|s| s.is_anchor()) {
+        let id = *data.session.selection.iter().next().unwrap();
+        menu = menu.append(MenuItem::new(
+            LocalizedString::new("menu-item-rename-anchor").with_placeholder("Rename Anchor…"),
+            consts::cmd::SHOW_ANCHOR_PROMPT.with(consts::cmd::AnchorTarget::Existing(id)),
+        ));
+    }
     menu
 }
 
@@ -40,12 +62,17 @@ pub(crate) fn make_menu(data: &AppState) -> MenuDesc<AppState> {
         menu = menu.append(platform_menus::mac::application::default());
     }
 
-    menu.append(file_menu(data))
+    menu = menu
+        .append(file_menu(data))
         .append(edit_menu())
         .append(view_menu())
         .append(glyph_menu(data))
         .append(paths_menu())
-        .append(tools_menu())
+        .append(tools_menu());
+    if !data.workspace.masters.is_empty() {
+        menu = menu.append(masters_menu(data));
+    }
+    menu
 }
 
 fn file_menu(data: &AppState) -> MenuDesc<AppState> {
@@ -55,8 +82,10 @@ fn file_menu(data: &AppState) -> MenuDesc<AppState> {
         .append(
             MenuItem::new(
                 LocalizedString::new("common-menu-file-open"),
-                commands::SHOW_OPEN_PANEL
-                    .with(FileDialogOptions::new().allowed_types(vec![UFO_FILE_TYPE])),
+                commands::SHOW_OPEN_PANEL.with(
+                    FileDialogOptions::new()
+                        .allowed_types(vec![UFO_FILE_TYPE, DESIGNSPACE_FILE_TYPE]),
+                ),
             )
             .hotkey(SysMods::Cmd, "o"),
         )
@@ -94,6 +123,21 @@ fn edit_menu<T: Data>() -> MenuDesc<T> {
         .append(platform_menus::common::cut().disabled())
         .append(platform_menus::common::copy())
         .append(platform_menus::common::paste())
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-paste-in-place").with_placeholder("Paste in Place"),
+                consts::cmd::PASTE_IN_PLACE,
+            )
+            .hotkey(SysMods::CmdShift, "V"),
+        )
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-paste-at-cursor")
+                    .with_placeholder("Paste at Cursor"),
+                consts::cmd::PASTE_AT_CURSOR,
+            )
+            .hotkey(SysMods::AltCmd, "V"),
+        )
         .append(MenuItem::new(
             LocalizedString::new("menu-item-delete").with_placeholder("Delete"),
             consts::cmd::DELETE,
@@ -113,6 +157,32 @@ fn edit_menu<T: Data>() -> MenuDesc<T> {
             )
             .hotkey(SysMods::AltCmd, "A"),
         )
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-select-on-curve")
+                .with_placeholder("Select All On-Curve Points"),
+            consts::cmd::SELECT_ON_CURVE,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-select-off-curve")
+                .with_placeholder("Select All Handles"),
+            consts::cmd::SELECT_OFF_CURVE,
+        ))
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-select-next-contour")
+                    .with_placeholder("Select Next Contour"),
+                consts::cmd::SELECT_NEXT_CONTOUR,
+            )
+            .hotkey(SysMods::Cmd, "]"),
+        )
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-select-prev-contour")
+                    .with_placeholder("Select Previous Contour"),
+                consts::cmd::SELECT_PREV_CONTOUR,
+            )
+            .hotkey(SysMods::Cmd, "["),
+        )
 }
 
 fn view_menu<T: Data>() -> MenuDesc<T> {
@@ -138,6 +208,32 @@ fn view_menu<T: Data>() -> MenuDesc<T> {
             )
             .hotkey(SysMods::Cmd, "0"),
         )
+        .append_separator()
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-show-handles").with_placeholder("Show Handles"),
+                consts::cmd::TOGGLE_HANDLES,
+            )
+            .hotkey(SysMods::Shift, "H"),
+        )
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-snap-to-grid").with_placeholder("Snap to Grid"),
+            consts::cmd::TOGGLE_GRID_SNAP,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-pixel-preview").with_placeholder("Pixel Preview"),
+            consts::cmd::TOGGLE_PIXEL_PREVIEW,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-highlight-overlaps")
+                .with_placeholder("Highlight Overlaps"),
+            consts::cmd::TOGGLE_OVERLAP_HIGHLIGHT,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-show-saved-diff")
+                .with_placeholder("Show Changes Since Save"),
+            consts::cmd::TOGGLE_SAVED_DIFF,
+        ))
 }
 
 fn glyph_menu(data: &AppState) -> MenuDesc<AppState> {
@@ -165,6 +261,48 @@ fn glyph_menu(data: &AppState) -> MenuDesc<AppState> {
             .hotkey(SysMods::CmdShift, "C")
             .disabled(),
         )
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-use-as-component")
+                .with_placeholder("Use as Component In…"),
+            consts::cmd::SHOW_USE_AS_COMPONENT_PROMPT,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-export-png").with_placeholder("Export PNG…"),
+            consts::cmd::SHOW_EXPORT_PNG_PROMPT,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-glyph-lib").with_placeholder("Glyph Lib…"),
+            consts::cmd::SHOW_GLYPH_LIB,
+        ))
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-apply-to-all-masters")
+                    .with_placeholder("Apply Last Edit to All Masters"),
+                consts::cmd::APPLY_TO_ALL_MASTERS,
+            )
+            .disabled_if(|| data.workspace.masters.len() < 2),
+        )
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-resync-metrics-keys")
+                .with_placeholder("Resync Metrics Keys"),
+            consts::cmd::RESYNC_METRICS_KEYS,
+        ))
+}
+
+fn masters_menu(data: &AppState) -> MenuDesc<AppState> {
+    let active = data.workspace.active_master();
+    data.workspace.masters.iter().enumerate().fold(
+        MenuDesc::new(LocalizedString::new("menu-masters-menu").with_placeholder("Masters")),
+        |menu, (idx, master)| {
+            menu.append(
+                MenuItem::new(
+                    LocalizedString::new("menu-item-master").with_placeholder(master.name.clone()),
+                    consts::cmd::SWITCH_MASTER.with(idx),
+                )
+                .selected(active == Some(idx)),
+            )
+        },
+    )
 }
 
 fn paths_menu<T: Data>() -> MenuDesc<T> {
@@ -174,6 +312,11 @@ fn paths_menu<T: Data>() -> MenuDesc<T> {
             consts::cmd::REVERSE_CONTOURS,
             // TODO: hotkey on mac should be ctrl-alt-cmd R, but what about non-mac?
         ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-correct-path-direction")
+                .with_placeholder("Correct Path Direction"),
+            consts::cmd::CORRECT_PATH_DIRECTION,
+        ))
         .append(
             MenuItem::new(
                 LocalizedString::new("menu-item-align-selection")
@@ -182,6 +325,81 @@ fn paths_menu<T: Data>() -> MenuDesc<T> {
             )
             .hotkey(SysMods::CmdShift, "A"),
         )
+        .append(align_menu())
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-toggle-tangent")
+                .with_placeholder("Toggle Tangent Point"),
+            consts::cmd::TOGGLE_TANGENT,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-smooth-selection").with_placeholder("Smooth Selection"),
+            consts::cmd::SMOOTH_SELECTION,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-make-g2-continuous")
+                .with_placeholder("Make Curvature Continuous"),
+            consts::cmd::MAKE_G2_CONTINUOUS,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-remove-stray-offcurves")
+                .with_placeholder("Remove Stray Off-Curve Points"),
+            consts::cmd::REMOVE_STRAY_OFFCURVES,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-close-all-contours")
+                .with_placeholder("Close Open Contours"),
+            consts::cmd::CLOSE_ALL_CONTOURS,
+        ))
+        .append_separator()
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-outset-outline").with_placeholder("Outset Outline"),
+            consts::cmd::OFFSET_OUTLINE.with(OUTLINE_OFFSET_STEP),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-inset-outline").with_placeholder("Inset Outline"),
+            consts::cmd::OFFSET_OUTLINE.with(-OUTLINE_OFFSET_STEP),
+        ))
+}
+
+fn align_menu<T: Data>() -> MenuDesc<T> {
+    MenuDesc::new(LocalizedString::new("menu-align-menu").with_placeholder("Align"))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-align-left").with_placeholder("Align Left"),
+            consts::cmd::ALIGN_SELECTION_TO.with(AlignKind::Left),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-align-right").with_placeholder("Align Right"),
+            consts::cmd::ALIGN_SELECTION_TO.with(AlignKind::Right),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-align-top").with_placeholder("Align Top"),
+            consts::cmd::ALIGN_SELECTION_TO.with(AlignKind::Top),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-align-bottom").with_placeholder("Align Bottom"),
+            consts::cmd::ALIGN_SELECTION_TO.with(AlignKind::Bottom),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-align-horizontal-centers")
+                .with_placeholder("Align Horizontal Centers"),
+            consts::cmd::ALIGN_SELECTION_TO.with(AlignKind::HorizontalCenter),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-align-vertical-centers")
+                .with_placeholder("Align Vertical Centers"),
+            consts::cmd::ALIGN_SELECTION_TO.with(AlignKind::VerticalCenter),
+        ))
+        .append_separator()
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-distribute-horizontally")
+                .with_placeholder("Distribute Horizontally"),
+            consts::cmd::DISTRIBUTE_SELECTION.with(Axis::Horizontal),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-distribute-vertically")
+                .with_placeholder("Distribute Vertically"),
+            consts::cmd::DISTRIBUTE_SELECTION.with(Axis::Vertical),
+        ))
 }
 
 fn tools_menu<T: Data>() -> MenuDesc<T> {
@@ -0,0 +1,206 @@
+//! Metrics keys, which link a glyph's sidebearings to those of another glyph.
+//!
+//! We read the keys that Glyphs.app writes (and glyphsLib preserves) in the
+//! glyph lib. A key names a reference glyph, such as `=n`; it can refer to
+//! the opposite side of the reference with a bar, as in `=|n`, and can add
+//! or subtract a constant, as in `=o+10`.
+
+use druid::Data;
+use norad::{Glyph, GlyphName};
+
+use crate::data::Workspace;
+
+const LEFT_METRICS_KEY: &str = "com.schriftgestaltung.Glyphs.glyph.leftMetricsKey";
+const RIGHT_METRICS_KEY: &str = "com.schriftgestaltung.Glyphs.glyph.rightMetricsKey";
+
+/// A side of a glyph.
+#[derive(Debug, Clone, Copy, Data, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A parsed metrics key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsKey {
+    /// The glyph whose sidebearing we follow.
+    pub reference: GlyphName,
+    /// If `true`, we follow the opposite side of the reference glyph.
+    pub opposite: bool,
+    /// A constant added to the reference sidebearing.
+    pub offset: f64,
+}
+
+/// A glyph whose sidebearing does not match its metrics key.
+#[derive(Debug, Clone, Data)]
+pub struct Mismatch {
+    pub glyph: GlyphName,
+    pub side: Side,
+    pub actual: f64,
+    pub expected: f64,
+    /// Set once a fix has been requested for this mismatch.
+    pub fixed: bool,
+}
+
+impl Side {
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+impl Mismatch {
+    /// The change to the sidebearing needed to match the metrics key.
+    pub fn delta(&self) -> f64 {
+        self.expected - self.actual
+    }
+}
+
+impl MetricsKey {
+    /// Parse a key such as `=n`, `=|n` or `=o-10`.
+    ///
+    /// Keys containing more complicated arithmetic are not supported.
+    pub fn parse(key: &str) -> Option<MetricsKey> {
+        let key = key.trim();
+        let key = key.strip_prefix('=').unwrap_or(key).trim();
+        let (opposite, key) = match key.strip_prefix('|') {
+            Some(rest) => (true, rest.trim()),
+            None => (false, key),
+        };
+
+        // only a trailing number is an offset; glyph names can contain '-'
+        let split = key.rfind(|c| c == '+' || c == '-').and_then(|idx| {
+            let offset = key[idx + 1..].trim().parse::<f64>().ok()?;
+            let sign = if key[idx..].starts_with('-') {
+                -1.0
+            } else {
+                1.0
+            };
+            Some((key[..idx].trim(), offset * sign))
+        });
+        let (name, offset) = split.unwrap_or((key, 0.0));
+
+        let is_operator = |c: char| "+*/()".contains(c) || c.is_whitespace();
+        if name.is_empty() || name.contains(is_operator) {
+            return None;
+        }
+
+        Some(MetricsKey {
+            reference: name.into(),
+            opposite,
+            offset,
+        })
+    }
+}
+
+/// Returns the metrics key for this side of the glyph, if it has one.
+pub fn metrics_key(glyph: &Glyph, side: Side) -> Option<MetricsKey> {
+    let lib_key = match side {
+        Side::Left => LEFT_METRICS_KEY,
+        Side::Right => RIGHT_METRICS_KEY,
+    };
+    glyph
+        .lib
+        .as_ref()?
+        .get(lib_key)?
+        .as_string()
+        .and_then(MetricsKey::parse)
+}
+
+/// The sidebearing this side of the glyph should have according to its
+/// metrics key, if it has one and the reference glyph can be measured.
+pub fn expected_sidebearing(workspace: &Workspace, glyph: &Glyph, side: Side) -> Option<f64> {
+    let key = metrics_key(glyph, side)?;
+    if key.reference == glyph.name {
+        return None;
+    }
+    let ref_side = if key.opposite { side.opposite() } else { side };
+    let sidebearings = workspace.sidebearings(&key.reference)?;
+    let reference = match ref_side {
+        Side::Left => sidebearings.left,
+        Side::Right => sidebearings.right,
+    };
+    Some(reference + key.offset)
+}
+
+/// Returns the sides of this glyph that don't match their metrics keys.
+pub fn glyph_mismatches(workspace: &Workspace, name: &GlyphName) -> Vec<Mismatch> {
+    let glyph = match workspace.glyph(name) {
+        Some(glyph) => glyph,
+        None => return Vec::new(),
+    };
+    let sidebearings = match workspace.sidebearings(name) {
+        Some(sidebearings) => sidebearings,
+        None => return Vec::new(),
+    };
+
+    let mut result = Vec::new();
+    for &side in &[Side::Left, Side::Right] {
+        let actual = match side {
+            Side::Left => sidebearings.left,
+            Side::Right => sidebearings.right,
+        };
+        if let Some(expected) = expected_sidebearing(workspace, glyph, side) {
+            if (expected - actual).abs() >= 1.0 {
+                result.push(Mismatch {
+                    glyph: name.clone(),
+                    side,
+                    actual,
+                    expected,
+                    fixed: false,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Check every glyph in the font, and return the sidebearings that don't
+/// match their metrics keys.
+pub fn find_mismatches(workspace: &Workspace) -> Vec<Mismatch> {
+    workspace
+        .font
+        .ufo
+        .iter_names()
+        .flat_map(|name| glyph_mismatches(workspace, &name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keys() {
+        let key = MetricsKey::parse("=n").unwrap();
+        assert_eq!(key.reference.as_ref(), "n");
+        assert!(!key.opposite);
+        assert_eq!(key.offset, 0.0);
+
+        let key = MetricsKey::parse("=|o").unwrap();
+        assert_eq!(key.reference.as_ref(), "o");
+        assert!(key.opposite);
+
+        let key = MetricsKey::parse("=H-20").unwrap();
+        assert_eq!(key.reference.as_ref(), "H");
+        assert_eq!(key.offset, -20.0);
+
+        let key = MetricsKey::parse("o + 5").unwrap();
+        assert_eq!(key.reference.as_ref(), "o");
+        assert_eq!(key.offset, 5.0);
+
+        let key = MetricsKey::parse("=a-cy").unwrap();
+        assert_eq!(key.reference.as_ref(), "a-cy");
+        assert_eq!(key.offset, 0.0);
+
+        let key = MetricsKey::parse("=a-cy-10").unwrap();
+        assert_eq!(key.reference.as_ref(), "a-cy");
+        assert_eq!(key.offset, -10.0);
+
+        assert!(MetricsKey::parse("=").is_none());
+        assert!(MetricsKey::parse("=n+x").is_none());
+        assert!(MetricsKey::parse("=n*2").is_none());
+    }
+}
@@ -205,8 +205,12 @@ impl Mouse {
         self.min_drag_distance = DEFAULT_MIN_DRAG_DISTANCE;
     }
 
+    /// Returns `true` if no mouse button is pressed.
+    pub fn is_up(&self) -> bool {
+        matches!(self.state, MouseState::Up(_))
+    }
+
     /// The current position of  the mouse.
-    #[allow(dead_code)]
     pub fn pos(&self) -> Point {
         match &self.state {
             MouseState::Up(e) => e.pos,
@@ -1,18 +1,35 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
 use std::sync::Arc;
 
 use super::design_space::{DPoint, DVec2, ViewPort};
 use druid::kurbo::{
-    Affine, BezPath, CubicBez, Line, ParamCurve, ParamCurveNearest, PathEl,
-    PathSeg as KurboPathSeg, Point, Vec2,
+    Affine, BezPath, CubicBez, Line, ParamCurve, ParamCurveArclen, ParamCurveNearest, PathEl,
+    PathSeg as KurboPathSeg, Point, Shape, Vec2,
 };
 use druid::Data;
+use norad::glyph::Identifier;
 
 use crate::selection::Selection;
 
 const RESERVED_ID_COUNT: usize = 5;
 const GUIDE_TYPE_ID: usize = 1;
+const ADVANCE_TYPE_ID: usize = 2;
+const COMPONENT_TYPE_ID: usize = 3;
+const ANCHOR_TYPE_ID: usize = 4;
+
+/// The smallest allowed denominator when computing a miter join; this limits
+/// the length of the miter at very sharp corners to 4x the offset distance.
+const MIN_MITER_DENOM: f64 = 0.125;
+
+/// The fraction of the remaining distance to the fair position that points
+/// move in a single smoothing pass.
+const SMOOTH_FACTOR: f64 = 0.5;
+/// The furthest, in design units, that smoothing moves a point from where it
+/// was before the first pass.
+const MAX_SMOOTH_DISTANCE: f64 = 8.0;
+/// The accuracy, in design units, of arc lengths used when healing a contour.
+const ARCLEN_ACCURACY: f64 = 0.1;
 
 /// We give paths & points unique integer identifiers.
 pub fn next_id() -> usize {
@@ -68,6 +85,10 @@ pub struct Path {
     points: Arc<Vec<PathPoint>>,
     trailing: Option<DPoint>,
     closed: bool,
+    /// UFO identifiers of points in this path, if they have them.
+    ///
+    /// These are kept to one side so that `PathPoint` can stay `Copy`.
+    identifiers: Arc<HashMap<EntityId, Identifier>>,
 }
 
 /// Questionable.
@@ -108,6 +129,37 @@ impl EntityId {
         self.parent == GUIDE_TYPE_ID
     }
 
+    #[inline]
+    pub fn new_for_component() -> Self {
+        EntityId::new_with_parent(COMPONENT_TYPE_ID)
+    }
+
+    pub fn is_component(self) -> bool {
+        self.parent == COMPONENT_TYPE_ID
+    }
+
+    #[inline]
+    pub fn new_for_anchor() -> Self {
+        EntityId::new_with_parent(ANCHOR_TYPE_ID)
+    }
+
+    pub fn is_anchor(self) -> bool {
+        self.parent == ANCHOR_TYPE_ID
+    }
+
+    /// The id of the glyph's advance width marker, which can be selected and
+    /// moved like a point.
+    pub fn advance_width() -> Self {
+        EntityId {
+            parent: ADVANCE_TYPE_ID,
+            point: 0,
+        }
+    }
+
+    pub fn is_advance_width(self) -> bool {
+        self.parent == ADVANCE_TYPE_ID
+    }
+
     pub(crate) fn parent_eq(self, other: EntityId) -> bool {
         self.parent == other.parent
     }
@@ -178,6 +230,7 @@ impl Path {
             points: Arc::new(vec![start]),
             closed: false,
             trailing: None,
+            identifiers: Arc::new(HashMap::new()),
         }
     }
 
@@ -208,6 +261,7 @@ impl Path {
             points: Arc::new(points),
             trailing,
             closed,
+            identifiers: Arc::new(HashMap::new()),
         }
     }
 
@@ -221,6 +275,13 @@ impl Path {
         Path::from_raw_parts(path_id, points, None, closed)
     }
 
+    /// A copy of this path, with new ids and without point identifiers.
+    pub(crate) fn duplicate(&self) -> Path {
+        let mut path = Path::from_points_ignoring_parent(self.points.to_vec(), self.closed);
+        path.trailing = self.trailing;
+        path
+    }
+
     /// Attempt to create a `Path` from a BezPath.
     ///
     /// - on the first 'segment' of the bezier will be used.
@@ -287,6 +348,7 @@ impl Path {
         let closed = !matches!(src.points[0].typ, NoradPType::Move);
 
         let path_id = next_id();
+        let mut identifiers = HashMap::new();
 
         let mut points: Vec<PathPoint> = src
             .points
@@ -309,6 +371,9 @@ impl Path {
                     parent: path_id,
                     point: next_id(),
                 };
+                if let Some(identifier) = src_point.identifier.clone() {
+                    identifiers.insert(id, identifier);
+                }
                 PathPoint { id, point, typ }
             })
             .collect();
@@ -317,7 +382,9 @@ impl Path {
             points.rotate_left(1);
         }
 
-        Path::from_raw_parts(path_id, points, None, closed)
+        let mut path = Path::from_raw_parts(path_id, points, None, closed);
+        path.identifiers = Arc::new(identifiers);
+        path
     }
 
     pub fn to_norad(&self) -> norad::glyph::Contour {
@@ -345,7 +412,7 @@ impl Path {
                 y,
                 typ,
                 smooth,
-                identifier: None,
+                identifier: self.identifiers.get(&p.id).cloned(),
                 name: None,
             });
             prev_off_curve = p.typ == PointType::OffCurve;
@@ -383,6 +450,28 @@ impl Path {
         Arc::make_mut(&mut self.points)
     }
 
+    /// The UFO identifier of this point, if it has one.
+    pub fn point_identifier(&self, point: EntityId) -> Option<&Identifier> {
+        self.identifiers.get(&point)
+    }
+
+    /// Set or clear the UFO identifier of this point.
+    pub fn set_point_identifier(&mut self, point: EntityId, identifier: Option<Identifier>) {
+        assert!(point.parent == self.id);
+        let identifiers = Arc::make_mut(&mut self.identifiers);
+        match identifier {
+            Some(identifier) => identifiers.insert(point, identifier),
+            None => identifiers.remove(&point),
+        };
+    }
+
+    /// Returns `true` if any point in this path has this identifier.
+    pub(crate) fn uses_identifier(&self, identifier: &Identifier) -> bool {
+        self.points
+            .iter()
+            .any(|p| self.identifiers.get(&p.id) == Some(identifier))
+    }
+
     pub fn iter_segments(&self) -> impl Iterator<Item = PathSeg> {
         let prev_pt = *self.start_point();
         let idx = if self.closed { 0 } else { 1 };
@@ -608,6 +697,46 @@ impl Path {
         self.transform_points(points, affine, DPoint::ZERO);
     }
 
+    /// Like `nudge_points`, but the handles of curves from a moved on-curve
+    /// point to a fixed one are scaled with the length of the curve's chord,
+    /// keeping their directions.
+    ///
+    /// Curves with a selected handle are moved as by `nudge_points`.
+    pub(crate) fn nudge_points_proportionally(&mut self, points: &[EntityId], v: DVec2) {
+        let old_segments: Vec<_> = self.iter_segments().collect();
+        self.nudge_points(points, v);
+
+        let moved = |pt: &PathPoint| points.contains(&pt.id);
+        for seg in old_segments {
+            let (p0, p1, p2, p3) = match seg {
+                PathSeg::Cubic(p0, p1, p2, p3) => (p0, p1, p2, p3),
+                PathSeg::Line(..) => continue,
+            };
+            if moved(&p0) == moved(&p3) || moved(&p1) || moved(&p2) {
+                continue;
+            }
+            let old_chord = (p3.point - p0.point).hypot();
+            if old_chord < 1.0 {
+                continue;
+            }
+            let (idx0, idx3) = match (self.idx_for_point(p0.id), self.idx_for_point(p3.id)) {
+                (Some(idx0), Some(idx3)) => (idx0, idx3),
+                _ => continue,
+            };
+            let new_p0 = self.points[idx0].point;
+            let new_p3 = self.points[idx3].point;
+            let ratio = (new_p3 - new_p0).hypot() / old_chord;
+            let new_p1 = new_p0.to_raw() + (p1.point - p0.point).to_raw() * ratio;
+            let new_p2 = new_p3.to_raw() + (p2.point - p3.point).to_raw() * ratio;
+            if let Some(idx) = self.idx_for_point(p1.id) {
+                self.points_mut()[idx].point = DPoint::from_raw(new_p1);
+            }
+            if let Some(idx) = self.idx_for_point(p2.id) {
+                self.points_mut()[idx].point = DPoint::from_raw(new_p2);
+            }
+        }
+    }
+
     pub(crate) fn nudge_all_points(&mut self, v: DVec2) {
         let affine = Affine::translate(v.to_raw());
         for idx in 0..self.points.len() {
@@ -625,16 +754,28 @@ impl Path {
     /// The `anchor` argument is a point that should be treated as the origin
     /// when applying the transform, which is used for things like scaling from
     /// a fixed point.
-    fn transform_points(&mut self, points: &[EntityId], affine: Affine, anchor: DPoint) {
+    pub(crate) fn transform_points(&mut self, points: &[EntityId], affine: Affine, anchor: DPoint) {
         let to_xform = self.points_for_points(points);
         for idx in &to_xform {
             self.transform_point(*idx, affine, anchor);
-            if !self.points[*idx].is_on_curve() {
-                if let Some((on_curve, handle)) = self.tangent_handle(*idx) {
-                    if !to_xform.contains(&handle) {
-                        self.adjust_handle_angle(*idx, on_curve, handle);
+            if self.points[*idx].is_on_curve() {
+                continue;
+            }
+            // a handle moving on its own keeps its smooth point smooth, by
+            // turning the other handle to match, or by staying on the line
+            // if its point is a tangent point.
+            match self.tangent_handle_opt(*idx) {
+                Some((on_curve, Some(handle))) if !to_xform.contains(&handle) => {
+                    self.adjust_handle_angle(*idx, on_curve, handle);
+                }
+                Some((on_curve, None)) if !to_xform.contains(&on_curve) => {
+                    if let Some(line_end) = self.tangent_line_end(on_curve) {
+                        let handle_pos = self.points[*idx].point;
+                        let new_pos = self.constrain_tangent_handle(on_curve, line_end, handle_pos);
+                        self.points_mut()[*idx].point = new_pos;
                     }
                 }
+                _ => (),
             }
         }
     }
@@ -670,16 +811,6 @@ impl Path {
         to_xform
     }
 
-    /// Returns the index for the on_curve point and the 'other' handle
-    /// for an offcurve point, if it exists.
-    fn tangent_handle(&self, idx: usize) -> Option<(usize, usize)> {
-        if let Some((on_curve, Some(bcp2))) = self.tangent_handle_opt(idx) {
-            Some((on_curve, bcp2))
-        } else {
-            None
-        }
-    }
-
     /// Return the index for the on_curve point, and the optional 'other' handle.
     fn tangent_handle_opt(&self, idx: usize) -> Option<(usize, Option<usize>)> {
         assert!(!self.points[idx].is_on_curve());
@@ -731,6 +862,9 @@ impl Path {
                 if is_locked {
                     dpt = axis_locked_point(dpt, self.points[on_curve].point);
                 }
+                if let Some(line_end) = self.tangent_line_end(on_curve) {
+                    dpt = self.constrain_tangent_handle(on_curve, line_end, dpt);
+                }
                 self.points_mut()[bcp1].point = dpt;
                 if let Some(bcp2) = bcp2 {
                     self.adjust_handle_angle(bcp1, on_curve, bcp2);
@@ -777,11 +911,13 @@ impl Path {
         match self.points[idx].typ {
             PointType::OffCurve => {
                 // delete both of the off curve points for this segment
+                // (a trailing handle on an open path has no sibling)
                 let other_id = if self.points[prev_idx].typ == PointType::OffCurve {
                     self.points[prev_idx].id
-                } else {
-                    assert!(self.points[next_idx].typ == PointType::OffCurve);
+                } else if self.points[next_idx].typ == PointType::OffCurve {
                     self.points[next_idx].id
+                } else {
+                    point_id
                 };
                 self.points_mut()
                     .retain(|p| p.id != point_id && p.id != other_id);
@@ -789,6 +925,18 @@ impl Path {
             _on_curve if self.points.len() == 1 => {
                 self.points_mut().clear();
             }
+            // the end of an open path has only one segment, which goes with it
+            _on_curve if !self.closed && (idx == 0 || idx == self.points.len() - 1) => {
+                let mut to_del = vec![idx];
+                let inward = |i: usize| if idx == 0 { i + 1 } else { i - 1 };
+                let mut neighbour = inward(idx);
+                while !self.points[neighbour].is_on_curve() {
+                    to_del.push(neighbour);
+                    neighbour = inward(neighbour);
+                }
+                let to_del: Vec<_> = to_del.iter().map(|i| self.points[*i].id).collect();
+                self.points_mut().retain(|p| !to_del.contains(&p.id));
+            }
             // with less than 4 points they must all be on curve
             _on_curve if self.points.len() == 4 => {
                 self.points_mut()
@@ -808,6 +956,7 @@ impl Path {
                 let prev = self.points[prev_idx];
                 let next = self.points[next_idx];
                 assert!(!prev.is_on_curve() && !next.is_on_curve());
+                self.merge_handles_around(idx);
                 let to_del = [prev.id, next.id, point_id];
                 self.points_mut().retain(|p| !to_del.contains(&p.id));
                 if self.points.len() == 3 {
@@ -844,6 +993,43 @@ impl Path {
         }
     }
 
+    /// Before the on-curve point at `idx` is removed from between two cubic
+    /// segments, stretch the outer handles so the single cubic that remains
+    /// keeps roughly the shape of the two.
+    ///
+    /// Each handle is scaled by the ratio of the combined arc length to that
+    /// of its own segment, which is close for arcs of a circle.
+    fn merge_handles_around(&mut self, idx: usize) {
+        let in_handle = self.prev_idx(idx);
+        let out_handle = self.next_idx(idx);
+        let start_handle = self.prev_idx(in_handle);
+        let end_handle = self.next_idx(out_handle);
+        let start = self.prev_idx(start_handle);
+        let end = self.next_idx(end_handle);
+        let is_cubic = !self.points[start_handle].is_on_curve()
+            && !self.points[end_handle].is_on_curve()
+            && self.points[start].is_on_curve()
+            && self.points[end].is_on_curve();
+        if !is_cubic {
+            return;
+        }
+
+        let raw = |i: usize| self.points[i].point.to_raw();
+        let first = CubicBez::new(raw(start), raw(start_handle), raw(in_handle), raw(idx));
+        let second = CubicBez::new(raw(idx), raw(out_handle), raw(end_handle), raw(end));
+        let len1 = first.arclen(ARCLEN_ACCURACY);
+        let len2 = second.arclen(ARCLEN_ACCURACY);
+        if len1 < f64::EPSILON || len2 < f64::EPSILON {
+            return;
+        }
+
+        let total = len1 + len2;
+        let new_start = first.p0 + (first.p1 - first.p0) * (total / len1);
+        let new_end = second.p3 + (second.p2 - second.p3) * (total / len2);
+        self.points_mut()[start_handle].point = DPoint::from_raw(new_start);
+        self.points_mut()[end_handle].point = DPoint::from_raw(new_end);
+    }
+
     /// Called when the user drags (modifying the bezier control points) after clicking.
     pub fn update_for_drag(&mut self, handle: DPoint) {
         assert!(!self.points.is_empty());
@@ -871,6 +1057,134 @@ impl Path {
         }
     }
 
+    /// Toggle an on-curve point between a corner and a tangent point.
+    ///
+    /// A tangent point is a smooth point that joins a line and a curve; its
+    /// handle is constrained to the direction of the line. Points that do not
+    /// sit between a line and a curve are left unchanged.
+    pub fn toggle_tangent_point_type(&mut self, id: EntityId) {
+        let idx = match self.idx_for_point(id) {
+            Some(idx) => idx,
+            None => return,
+        };
+        let is_endpoint = !self.closed && (idx == 0 || idx == self.points.len() - 1);
+        if is_endpoint || !self.points[idx].is_on_curve() {
+            return;
+        }
+
+        let prev = self.prev_idx(idx);
+        let next = self.next_idx(idx);
+        let (line_end, handle) = match (
+            self.points[prev].is_on_curve(),
+            self.points[next].is_on_curve(),
+        ) {
+            (true, false) => (prev, next),
+            (false, true) => (next, prev),
+            _ => return,
+        };
+
+        if self.points[idx].typ == PointType::OnCurveSmooth {
+            self.points_mut()[idx].typ = PointType::OnCurve;
+        } else {
+            self.points_mut()[idx].typ = PointType::OnCurveSmooth;
+            let handle_pos = self.points[handle].point;
+            let new_pos = self.constrain_tangent_handle(idx, line_end, handle_pos);
+            self.points_mut()[handle].point = new_pos;
+        }
+    }
+
+    /// Make the on-curve point `id` a smooth point or a corner point,
+    /// returning `true` if it changed.
+    ///
+    /// A point that becomes smooth has its handles made collinear: the
+    /// shorter handle is turned to point away from the longer one, or, if the
+    /// point joins a line and a curve, the handle is aligned with the line.
+    /// Points without handles and the ends of open paths cannot be smooth.
+    pub fn set_point_smooth(&mut self, id: EntityId, smooth: bool) -> bool {
+        let idx = match self.idx_for_point(id) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        let typ = self.points[idx].typ;
+        if !typ.is_on_curve() || (typ == PointType::OnCurveSmooth) == smooth {
+            return false;
+        }
+        if !smooth {
+            self.points_mut()[idx].typ = PointType::OnCurve;
+            return true;
+        }
+        let is_endpoint = !self.closed && (idx == 0 || idx == self.points.len() - 1);
+        if is_endpoint {
+            return false;
+        }
+
+        let prev = self.prev_idx(idx);
+        let next = self.next_idx(idx);
+        match (
+            self.points[prev].is_on_curve(),
+            self.points[next].is_on_curve(),
+        ) {
+            (true, true) => return false,
+            (true, false) | (false, true) => {
+                let (line_end, handle) = if self.points[prev].is_on_curve() {
+                    (prev, next)
+                } else {
+                    (next, prev)
+                };
+                let handle_pos = self.points[handle].point;
+                let new_pos = self.constrain_tangent_handle(idx, line_end, handle_pos);
+                self.points_mut()[handle].point = new_pos;
+            }
+            (false, false) => {
+                let origin = self.points[idx].point;
+                let to_prev = (self.points[prev].point - origin).to_raw();
+                let to_next = (self.points[next].point - origin).to_raw();
+                let (reference, shorter, len) = if to_prev.hypot() >= to_next.hypot() {
+                    (to_prev, next, to_next.hypot())
+                } else {
+                    (to_next, prev, to_prev.hypot())
+                };
+                if reference.hypot() > 0.0 {
+                    let new_pos = origin + DVec2::from_raw(-reference.normalize() * len);
+                    self.points_mut()[shorter].point = new_pos;
+                }
+            }
+        }
+        self.points_mut()[idx].typ = PointType::OnCurveSmooth;
+        true
+    }
+
+    /// If the on-curve point at `on_curve` is a tangent point, returns the
+    /// index of the on-curve point at the other end of its line segment.
+    fn tangent_line_end(&self, on_curve: usize) -> Option<usize> {
+        if self.points[on_curve].typ != PointType::OnCurveSmooth {
+            return None;
+        }
+        let prev = self.prev_idx(on_curve);
+        let next = self.next_idx(on_curve);
+        match (
+            self.points[prev].is_on_curve(),
+            self.points[next].is_on_curve(),
+        ) {
+            (true, false) => Some(prev),
+            (false, true) => Some(next),
+            _ => None,
+        }
+    }
+
+    /// Project `handle` onto the ray that continues the line from `line_end`
+    /// through the tangent point at `on_curve`.
+    fn constrain_tangent_handle(&self, on_curve: usize, line_end: usize, handle: DPoint) -> DPoint {
+        let origin = self.points[on_curve].point;
+        let line_dir = (origin - self.points[line_end].point).to_raw();
+        if line_dir.hypot() == 0.0 {
+            return handle;
+        }
+        let line_dir = line_dir.normalize();
+        let len = (handle - origin).to_raw().dot(line_dir).max(0.0);
+        origin + DVec2::from_raw(line_dir * len)
+    }
+
     /// If the user drags after mousedown, we convert the last point to a curve.
     ///
     /// TODO: So this doesn't quite match the Glyphs logic. There, the "trailing"
@@ -918,6 +1232,23 @@ impl Path {
         self.trailing = Some(handle);
     }
 
+    /// Returns `true` if this is an on-curve point at the top or bottom of
+    /// the contour locally, such as the top of an 'o'.
+    pub(crate) fn is_vertical_extremum(&self, point: EntityId) -> bool {
+        let idx = match self.idx_for_point(point) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        let is_end = !self.closed && (idx == 0 || idx == self.points.len() - 1);
+        if is_end || self.points.len() < 3 || !self.points[idx].is_on_curve() {
+            return false;
+        }
+        let y = self.points[idx].point.y;
+        let prev = self.points[self.prev_idx(idx)].point.y - y;
+        let next = self.points[self.next_idx(idx)].point.y - y;
+        prev * next >= 0.0
+    }
+
     pub(crate) fn align_point(&mut self, point: EntityId, val: f64, set_x: bool) {
         if let Some(idx) = self.idx_for_point(point) {
             let points = self.points_mut();
@@ -938,6 +1269,71 @@ impl Path {
         self.points.last().unwrap().id
     }
 
+    /// Close an open path with more than one point, returning `true` if it
+    /// was closed.
+    ///
+    /// If the path ends where it starts, the end point is merged into the
+    /// start point; otherwise the ends are joined with a line.
+    pub(crate) fn close_joining_ends(&mut self) -> bool {
+        if self.closed || self.points.len() < 2 {
+            return false;
+        }
+        let start = self.points[0].point;
+        if self.points.len() > 2 && self.points.last().map(|p| p.point) == Some(start) {
+            self.points_mut().pop();
+        }
+        self.trailing = None;
+        self.close();
+        true
+    }
+
+    /// Append the open path `other` to this open path, joining our last
+    /// point to its first.
+    ///
+    /// If the two points coincide they are welded into our last point;
+    /// otherwise they are joined with a line. Handles keep their positions.
+    /// Returns the ids of the points either side of the join, which are the
+    /// same if the points were welded.
+    pub(crate) fn append_path(&mut self, other: &Path) -> (EntityId, EntityId) {
+        assert!(!self.closed && !other.closed);
+        let end = *self.points.last().unwrap();
+        let welded = other.points[0].point == end.point;
+        let skip = if welded { 1 } else { 0 };
+
+        for pt in other.points.iter().skip(skip) {
+            let id = EntityId::new_with_parent(self.id);
+            if let Some(identifier) = other.identifiers.get(&pt.id) {
+                Arc::make_mut(&mut self.identifiers).insert(id, identifier.clone());
+            }
+            self.points_mut().push(PathPoint { id, ..*pt });
+        }
+        self.trailing = None;
+        if welded {
+            (end.id, end.id)
+        } else {
+            let end_idx = self.points.len() - other.points.len() - 1;
+            (end.id, self.points[end_idx + 1].id)
+        }
+    }
+
+    /// Returns `true` if this closed path surrounds `other`.
+    ///
+    /// `other` is surrounded if it lies within our bounding box, and its
+    /// first point is inside us.
+    pub(crate) fn encloses(&self, other: &Path) -> bool {
+        let first = match other.points.first() {
+            Some(point) => point.point.to_raw(),
+            None => return false,
+        };
+        if !self.closed {
+            return false;
+        }
+        let bez = self.bezier();
+        let bbox = bez.bounding_box();
+        let other_bbox = other.bezier().bounding_box();
+        bbox.union(other_bbox) == bbox && bbox != other_bbox && bez.contains(first)
+    }
+
     pub fn reverse_contour(&mut self) {
         let last = if self.closed {
             self.points.len() - 1
@@ -947,6 +1343,288 @@ impl Path {
         self.points_mut()[..last].reverse();
     }
 
+    /// Move every point of a closed path by `distance` along the path's normals.
+    ///
+    /// Positive distances outset and negative distances inset the outline,
+    /// assuming the path has PostScript direction (counter-clockwise outer
+    /// contours). Corners are joined with a limited miter. Handles keep
+    /// their direction, and are scaled by the change in the radius of
+    /// curvature at their on-curve point, so that curves are offset evenly.
+    /// Large offsets may produce self-intersections, which have to be removed
+    /// separately.
+    pub(crate) fn offset(&mut self, distance: f64) {
+        if !self.closed || self.points.len() < 2 {
+            return;
+        }
+
+        let normals: Vec<_> = (0..self.points.len())
+            .map(|idx| self.offset_normals(idx))
+            .collect();
+        let new_points: Vec<_> = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(idx, pt)| {
+                if pt.is_on_curve() {
+                    let offset = normals[idx].map(|(n_in, n_out)| miter_join(n_in, n_out));
+                    let offset = offset.unwrap_or(Vec2::ZERO) * distance;
+                    return DPoint::from_raw(pt.point.to_raw() + offset);
+                }
+                let (on, offset) = if self.points[self.prev_idx(idx)].is_on_curve() {
+                    let on = self.prev_idx(idx);
+                    (on, normals[on].map(|(_, n_out)| n_out))
+                } else {
+                    let on = self.next_idx(idx);
+                    (on, normals[on].map(|(n_in, _)| n_in))
+                };
+                let on_pt = self.points[on].point.to_raw();
+                let new_on = on_pt + offset.unwrap_or(Vec2::ZERO) * distance;
+                let scale = (1.0 + distance * self.curvature_at_handle(on, idx)).max(0.0);
+                DPoint::from_raw(new_on + (pt.point.to_raw() - on_pt) * scale)
+            })
+            .collect();
+
+        for (point, new_pos) in self.points_mut().iter_mut().zip(new_points) {
+            point.point = new_pos;
+        }
+    }
+
+    /// For an on-curve point, returns the unit normals of the incoming and
+    /// outgoing directions of the path at that point.
+    fn offset_normals(&self, idx: usize) -> Option<(Vec2, Vec2)> {
+        if !self.points[idx].is_on_curve() {
+            return None;
+        }
+        let pt = self.points[idx].point.to_raw();
+        let prev = self.distinct_neighbour(idx, false)?;
+        let next = self.distinct_neighbour(idx, true)?;
+        Some((right_normal(pt - prev), right_normal(next - pt)))
+    }
+
+    /// The signed curvature of the cubic segment that this handle belongs to,
+    /// at the on-curve point `on` that the handle is attached to.
+    ///
+    /// This is positive where a segment turns left, as the segments of a
+    /// counter-clockwise contour do.
+    fn curvature_at_handle(&self, on: usize, handle: usize) -> f64 {
+        let forward = self.next_idx(on) == handle;
+        let far = if forward {
+            self.next_idx(handle)
+        } else {
+            self.prev_idx(handle)
+        };
+        if self.points[far].is_on_curve() {
+            return 0.0;
+        }
+        let start = self.points[on].point.to_raw();
+        let handle = self.points[handle].point.to_raw();
+        let far = self.points[far].point.to_raw();
+        let d1 = handle - start;
+        let d2 = (far - handle) - d1;
+        let len = d1.hypot();
+        if len == 0.0 {
+            return 0.0;
+        }
+        let curvature = 2.0 / 3.0 * d1.cross(d2) / len.powi(3);
+        if forward {
+            curvature
+        } else {
+            -curvature
+        }
+    }
+
+    /// Returns the position of the nearest point before or after `idx` that
+    /// is not coincident with it.
+    fn distinct_neighbour(&self, idx: usize, forward: bool) -> Option<Point> {
+        let pt = self.points[idx].point;
+        let mut cur = idx;
+        for _ in 1..self.points.len() {
+            cur = if forward {
+                self.next_idx(cur)
+            } else {
+                self.prev_idx(cur)
+            };
+            if self.points[cur].point != pt {
+                return Some(self.points[cur].point.to_raw());
+            }
+        }
+        None
+    }
+
+    /// Make the curves through these on-curve points a little fairer.
+    ///
+    /// The handles of each smooth point are rotated partway towards their
+    /// shared tangent, and the point itself moves partway towards the line
+    /// between its handles. Each pass has less effect than the last, and
+    /// points are kept within `MAX_SMOOTH_DISTANCE` of their positions in
+    /// `original`, the path before the first pass. Corner points, and points
+    /// without a handle on each side, are left unchanged.
+    pub(crate) fn smooth_points(&mut self, points: &[EntityId], original: &Path) {
+        let indices: Vec<_> = points
+            .iter()
+            .filter_map(|id| self.idx_for_point(*id))
+            .filter(|idx| self.can_smooth(*idx))
+            .collect();
+
+        for idx in indices {
+            let prev = self.prev_idx(idx);
+            let next = self.next_idx(idx);
+            let pt = self.points[idx].point.to_raw();
+            let handle_in = self.points[prev].point.to_raw();
+            let handle_out = self.points[next].point.to_raw();
+            let len_in = (pt - handle_in).hypot();
+            let len_out = (handle_out - pt).hypot();
+            if len_in == 0.0 || len_out == 0.0 {
+                continue;
+            }
+
+            let tangent = (pt - handle_in) / len_in + (handle_out - pt) / len_out;
+            if tangent.hypot() == 0.0 {
+                continue;
+            }
+            let tangent = tangent.normalize();
+            let new_in = smoothing_step(handle_in, pt - tangent * len_in);
+            let new_out = smoothing_step(handle_out, pt + tangent * len_out);
+            let fair_pt = new_in.lerp(new_out, len_in / (len_in + len_out));
+            let new_pt = smoothing_step(pt, fair_pt);
+
+            let clamp = |idx: usize, pos: Point| {
+                let id = self.points[idx].id;
+                match original.path_point_for_id(id) {
+                    Some(start) => clamp_smoothing(start.point.to_raw(), pos),
+                    None => pos,
+                }
+            };
+            let new_in = clamp(prev, new_in);
+            let new_out = clamp(next, new_out);
+            let new_pt = clamp(idx, new_pt);
+
+            let points = self.points_mut();
+            points[prev].point = DPoint::from_raw(new_in);
+            points[next].point = DPoint::from_raw(new_out);
+            points[idx].point = DPoint::from_raw(new_pt);
+        }
+    }
+
+    /// Remove off-curve points that are not part of a valid cubic segment,
+    /// returning the number of points removed.
+    ///
+    /// A lone off-curve point between two on-curve points is removed, making
+    /// that segment a line; if there are more than two, only the first and
+    /// last are kept. Off-curve points after the end of an open path are also
+    /// removed.
+    pub(crate) fn remove_stray_off_curves(&mut self) -> usize {
+        let len = self.points.len();
+        let on_curve: Vec<usize> = (0..len).filter(|i| self.points[*i].is_on_curve()).collect();
+        let mut gaps: Vec<(usize, usize)> = on_curve.windows(2).map(|w| (w[0], w[1])).collect();
+        if let (Some(first), Some(last)) = (on_curve.first(), on_curve.last()) {
+            // for a closed path, the gap that wraps around to the start
+            if self.closed {
+                gaps.push((*last, first + len));
+            }
+        }
+
+        let mut stray = HashSet::new();
+        for (start, end) in gaps {
+            let count = end - start - 1;
+            if count == 1 {
+                stray.insert((start + 1) % len);
+            } else if count > 2 {
+                stray.extend((start + 2..end - 1).map(|i| i % len));
+            }
+        }
+        if !self.closed {
+            if let Some(last) = on_curve.last() {
+                stray.extend(last + 1..len);
+            }
+        }
+        if stray.is_empty() {
+            return 0;
+        }
+
+        let mut idx = 0;
+        self.points_mut().retain(|_| {
+            idx += 1;
+            !stray.contains(&(idx - 1))
+        });
+
+        // smooth points that no longer have handles are corners
+        for idx in 0..self.points.len() {
+            if self.points[idx].typ == PointType::OnCurveSmooth
+                && self.points[self.prev_idx(idx)].is_on_curve()
+                && self.points[self.next_idx(idx)].is_on_curve()
+            {
+                self.points_mut()[idx].typ = PointType::OnCurve;
+            }
+        }
+        stray.len()
+    }
+
+    /// Adjust the handle lengths at these smooth points so that the curvature
+    /// is approximately the same on either side (G2 continuity).
+    ///
+    /// The curvature at the end of a cubic is proportional to the distance of
+    /// its far control point from the tangent, divided by the square of the
+    /// handle length; we set both handle lengths so that each side has the
+    /// geometric mean of the current curvatures. The handles are also aligned
+    /// to their shared tangent. Points without a cubic on each side, and points
+    /// at an inflection, are left unchanged.
+    pub(crate) fn make_g2_continuous(&mut self, points: &[EntityId]) {
+        let indices: Vec<_> = points
+            .iter()
+            .filter_map(|id| self.idx_for_point(*id))
+            .filter(|idx| self.can_smooth(*idx))
+            .collect();
+
+        for idx in indices {
+            let prev = self.prev_idx(idx);
+            let next = self.next_idx(idx);
+            let far_prev = self.prev_idx(prev);
+            let far_next = self.next_idx(next);
+            if self.points[far_prev].is_on_curve() || self.points[far_next].is_on_curve() {
+                continue;
+            }
+
+            let pt = self.points[idx].point.to_raw();
+            let handle_in = self.points[prev].point.to_raw();
+            let handle_out = self.points[next].point.to_raw();
+            let len_in = (pt - handle_in).hypot();
+            let len_out = (handle_out - pt).hypot();
+            if len_in == 0.0 || len_out == 0.0 || handle_in == handle_out {
+                continue;
+            }
+
+            let tangent = (handle_out - handle_in).normalize();
+            let dist_in = tangent.cross(self.points[far_prev].point.to_raw() - pt);
+            let dist_out = tangent.cross(self.points[far_next].point.to_raw() - pt);
+            // zero curvature, or curving in different directions
+            if dist_in * dist_out <= 0.0 {
+                continue;
+            }
+
+            let curve_in = dist_in.abs() / (len_in * len_in);
+            let curve_out = dist_out.abs() / (len_out * len_out);
+            let target = (curve_in * curve_out).sqrt();
+            let new_len_in = (dist_in.abs() / target).sqrt();
+            let new_len_out = (dist_out.abs() / target).sqrt();
+
+            let points = self.points_mut();
+            points[prev].point = DPoint::from_raw(pt - tangent * new_len_in);
+            points[next].point = DPoint::from_raw(pt + tangent * new_len_out);
+        }
+    }
+
+    /// Returns `true` if the point at `idx` is a smooth point with an
+    /// off-curve point on either side.
+    fn can_smooth(&self, idx: usize) -> bool {
+        let is_endpoint = !self.closed && (idx == 0 || idx == self.points.len() - 1);
+        !is_endpoint
+            && self.points.len() > 2
+            && self.points[idx].typ == PointType::OnCurveSmooth
+            && !self.points[self.prev_idx(idx)].is_on_curve()
+            && !self.points[self.next_idx(idx)].is_on_curve()
+    }
+
     #[inline]
     fn prev_idx(&self, idx: usize) -> usize {
         if idx == 0 {
@@ -970,6 +1648,28 @@ impl Path {
         self.idx_for_point(point).map(|idx| self.points[idx])
     }
 
+    /// The nearest on-curve point after `point`, or before it if `forward`
+    /// is `false`, wrapping around at the ends of the path.
+    ///
+    /// Returns `point` if there is no other on-curve point.
+    pub(crate) fn adjacent_on_curve(&self, point: EntityId, forward: bool) -> EntityId {
+        let mut idx = match self.idx_for_point(point) {
+            Some(idx) => idx,
+            None => return point,
+        };
+        for _ in 1..self.points.len() {
+            idx = if forward {
+                self.next_idx(idx)
+            } else {
+                self.prev_idx(idx)
+            };
+            if self.points[idx].is_on_curve() {
+                return self.points[idx].id;
+            }
+        }
+        point
+    }
+
     pub(crate) fn prev_point(&self, point: EntityId) -> PathPoint {
         assert!(point.parent == self.id);
         let idx = self.idx_for_point(point).expect("bad input to prev_point");
@@ -984,7 +1684,9 @@ impl Path {
         self.points[idx]
     }
 
-    pub(crate) fn split_segment_at_point(&mut self, seg: PathSeg, t: f64) {
+    /// Split `seg` at `t` without changing its shape, returning the id of
+    /// the new on-curve point.
+    pub(crate) fn split_segment_at_point(&mut self, seg: PathSeg, t: f64) -> EntityId {
         let (existing_control_pts, points_to_insert) = match seg {
             PathSeg::Line(..) => (0, 1),
             PathSeg::Cubic(..) => (2, 5),
@@ -994,6 +1696,7 @@ impl Path {
         if let PathSeg::Cubic(_, _, _, p3) = &mut pre_seg {
             p3.typ = PointType::OnCurveSmooth;
         }
+        let new_point = pre_seg.end_id();
         let post_seg = seg.subsegment(t..1.0);
         let mut insert_idx = self.idx_for_point(seg.start_id()).unwrap();
         insert_idx = self.next_idx(insert_idx);
@@ -1011,6 +1714,7 @@ impl Path {
                 next_pt
             }),
         );
+        new_point
     }
 
     /// Upgrade a line segment to a cubic bezier.
@@ -1066,6 +1770,35 @@ pub(crate) fn mark_tangent_handles(points: &mut [PathPoint]) {
     }
 }
 
+/// Move `from` partway towards `to`.
+fn smoothing_step(from: Point, to: Point) -> Point {
+    from + (to - from) * SMOOTH_FACTOR
+}
+
+/// Limit `pos` to within `MAX_SMOOTH_DISTANCE` of `start`.
+fn clamp_smoothing(start: Point, pos: Point) -> Point {
+    let offset = pos - start;
+    let len = offset.hypot();
+    if len > MAX_SMOOTH_DISTANCE {
+        start + offset * (MAX_SMOOTH_DISTANCE / len)
+    } else {
+        pos
+    }
+}
+
+/// The unit normal to the right of the direction `v`.
+fn right_normal(v: Vec2) -> Vec2 {
+    let v = v.normalize();
+    Vec2::new(v.y, -v.x)
+}
+
+/// The offset of a corner whose sides have the unit normals `n_in` and `n_out`,
+/// such that both sides are moved by one unit.
+fn miter_join(n_in: Vec2, n_out: Vec2) -> Vec2 {
+    let denom = (1.0 + n_in.dot(n_out)).max(MIN_MITER_DENOM);
+    (n_in + n_out) / denom
+}
+
 /// Lock the smallest axis of `point` (from `prev`) to that axis on `prev`.
 fn axis_locked_point(point: DPoint, prev: DPoint) -> DPoint {
     let dxy = prev - point;
@@ -1241,7 +1974,7 @@ impl std::fmt::Debug for PathSeg {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use druid::kurbo::{Rect, Shape};
+    use druid::kurbo::{Circle, Rect};
 
     #[test]
     fn from_bezpath() {
@@ -1300,4 +2033,397 @@ mod tests {
         assert_eq!(iter.next(), Some(Line::new((0., 0.), (20., 0.)).into()));
         assert_eq!(iter.next(), Some(Line::new((20., 0.), (10., 10.)).into()));
     }
+
+    #[test]
+    fn toggle_tangent_constrains_handle() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.line_to((100., 0.));
+        bez.curve_to((150., 20.), (200., 50.), (200., 100.));
+        bez.close_path();
+
+        let mut path = Path::from_bezpath(bez).unwrap();
+        let tangent = path
+            .points()
+            .iter()
+            .find(|p| p.point == DPoint::new(100., 0.))
+            .map(|p| p.id)
+            .unwrap();
+
+        path.toggle_tangent_point_type(tangent);
+        assert_eq!(
+            path.path_point_for_id(tangent).unwrap().typ,
+            PointType::OnCurveSmooth
+        );
+        let handle = path.next_point(tangent);
+        assert_eq!(handle.point, DPoint::new(150., 0.));
+
+        // dragging the handle keeps it on the line
+        path.update_handle(handle.id, DPoint::new(180., 40.), false);
+        assert_eq!(path.next_point(tangent).point, DPoint::new(180., 0.));
+
+        path.toggle_tangent_point_type(tangent);
+        assert_eq!(
+            path.path_point_for_id(tangent).unwrap().typ,
+            PointType::OnCurve
+        );
+    }
+
+    #[test]
+    fn smooth_aligns_handles() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (40., 100.), (100., 100.));
+        bez.curve_to((160., 120.), (200., 50.), (200., 0.));
+        bez.close_path();
+
+        let mut path = Path::from_bezpath(bez).unwrap();
+        let smooth = path
+            .points()
+            .iter()
+            .find(|p| p.point == DPoint::new(100., 100.))
+            .map(|p| p.id)
+            .unwrap();
+        path.toggle_on_curve_point_type(smooth);
+        let corner = *path.start_point();
+
+        let kink = |path: &Path| {
+            let pt = path.path_point_for_id(smooth).unwrap().point.to_raw();
+            let d_in = (pt - path.prev_point(smooth).point.to_raw()).normalize();
+            let d_out = (path.next_point(smooth).point.to_raw() - pt).normalize();
+            d_in.cross(d_out).abs()
+        };
+
+        let original = path.clone();
+        let before = kink(&path);
+        path.smooth_points(&[smooth, corner.id], &original);
+        let after = kink(&path);
+        assert!(after < before);
+        path.smooth_points(&[smooth, corner.id], &original);
+        assert!(kink(&path) < after);
+
+        // repeated passes don't drift further than the limit
+        for _ in 0..20 {
+            path.smooth_points(&[smooth, corner.id], &original);
+        }
+        for point in original.points() {
+            let moved = path.path_point_for_id(point.id).unwrap().point;
+            let distance = (moved.to_raw() - point.point.to_raw()).hypot();
+            assert!(distance <= MAX_SMOOTH_DISTANCE + 1.0, "moved {}", distance);
+        }
+
+        // corner points are not smoothed
+        assert_eq!(
+            path.path_point_for_id(corner.id).unwrap().point,
+            corner.point
+        );
+    }
+
+    #[test]
+    fn set_point_smooth_keeps_longer_handle() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (40., 100.), (100., 100.));
+        bez.curve_to((160., 120.), (200., 50.), (200., 0.));
+        bez.close_path();
+
+        let mut path = Path::from_bezpath(bez).unwrap();
+        let point = path
+            .points()
+            .iter()
+            .find(|p| p.point == DPoint::new(100., 100.))
+            .map(|p| p.id)
+            .unwrap();
+
+        assert!(!path.set_point_smooth(point, false));
+        assert!(path.set_point_smooth(point, true));
+        assert_eq!(
+            path.path_point_for_id(point).unwrap().typ,
+            PointType::OnCurveSmooth
+        );
+        // the longer, outgoing handle is untouched, and the incoming handle
+        // keeps its length but now points the other way
+        assert_eq!(path.next_point(point).point, DPoint::new(160., 120.));
+        assert_eq!(path.prev_point(point).point, DPoint::new(43., 81.));
+
+        assert!(path.set_point_smooth(point, false));
+        assert_eq!(
+            path.path_point_for_id(point).unwrap().typ,
+            PointType::OnCurve
+        );
+    }
+
+    #[test]
+    fn split_segment_returns_new_point() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 100.), (200., 100.), (200., 0.));
+        let mut path = Path::from_bezpath(bez).unwrap();
+        let seg = path.iter_segments().next().unwrap();
+
+        let new_point = path.split_segment_at_point(seg, 0.5);
+        assert_eq!(path.points().len(), 7);
+        let point = path.path_point_for_id(new_point).unwrap();
+        assert_eq!(point.point, DPoint::new(100., 75.));
+        assert_eq!(point.typ, PointType::OnCurveSmooth);
+        assert_eq!(path.prev_point(new_point).point, DPoint::new(50., 75.));
+    }
+
+    #[test]
+    fn deleting_points_heals_the_contour() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 55.), (45., 100.), (100., 100.));
+        bez.curve_to((155., 100.), (200., 55.), (200., 0.));
+        let path = Path::from_bezpath(bez).unwrap();
+        let id_at = |path: &Path, x, y| {
+            let point = DPoint::new(x, y);
+            path.points().iter().find(|p| p.point == point).unwrap().id
+        };
+        let positions = |path: &Path| -> Vec<Point> {
+            path.points().iter().map(|p| p.point.to_raw()).collect()
+        };
+
+        // the outer handles are stretched to span both segments
+        let mut merged = path.clone();
+        merged.delete_points(&[id_at(&path, 100., 100.)]);
+        let expected = [(0., 0.), (0., 110.), (200., 110.), (200., 0.)];
+        let expected: Vec<Point> = expected.iter().map(|&p| p.into()).collect();
+        assert_eq!(positions(&merged), expected);
+
+        // deleting a handle turns its segment into a line
+        let mut line = path.clone();
+        line.delete_points(&[id_at(&path, 45., 100.)]);
+        assert_eq!(line.points().len(), 5);
+        assert!(line.points()[1].is_on_curve());
+
+        // an endpoint takes its only segment with it
+        let mut shortened = path.clone();
+        shortened.delete_points(&[id_at(&path, 0., 0.)]);
+        assert_eq!(positions(&shortened)[0], Point::new(100., 100.));
+        assert_eq!(shortened.points().len(), 4);
+    }
+
+    #[test]
+    fn moving_a_lone_handle_keeps_points_smooth() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (40., 100.), (100., 100.));
+        bez.curve_to((160., 120.), (200., 50.), (200., 0.));
+        bez.line_to((100., 0.));
+        bez.curve_to((50., 20.), (0., -20.), (0., -10.));
+        bez.close_path();
+
+        let mut path = Path::from_bezpath(bez).unwrap();
+        let find = |path: &Path, pt: DPoint| {
+            path.points()
+                .iter()
+                .find(|p| p.point == pt)
+                .map(|p| p.id)
+                .unwrap()
+        };
+        let smooth = find(&path, DPoint::new(100., 100.));
+        path.set_point_smooth(smooth, true);
+        let (prev, next) = (path.prev_point(smooth).id, path.next_point(smooth).id);
+
+        // the other handle turns to match, keeping its length
+        path.nudge_points(&[next], DVec2::from_raw((0., -20.)));
+        assert_eq!(path.next_point(smooth).point, DPoint::new(160., 100.));
+        assert_eq!(path.prev_point(smooth).point, DPoint::new(40., 100.));
+
+        // both handles move together
+        path.nudge_points(&[prev, next], DVec2::from_raw((0., 10.)));
+        assert_eq!(path.next_point(smooth).point, DPoint::new(160., 110.));
+        assert_eq!(path.prev_point(smooth).point, DPoint::new(40., 110.));
+
+        // a tangent point's handle stays on the line
+        let tangent = find(&path, DPoint::new(100., 0.));
+        path.toggle_tangent_point_type(tangent);
+        let handle = path.next_point(tangent).id;
+        path.nudge_points(&[handle], DVec2::from_raw((-20., 30.)));
+        assert_eq!(path.next_point(tangent).point, DPoint::new(30., 0.));
+    }
+
+    #[test]
+    fn remove_stray_off_curves() {
+        let id = next_id();
+        let on = |x, y| PathPoint::on_curve(id, DPoint::new(x, y));
+        let off = |x, y| PathPoint::off_curve(id, DPoint::new(x, y));
+        let points = vec![
+            on(100., 0.),
+            off(100., 50.),
+            on(100., 100.),
+            off(50., 150.),
+            off(0., 150.),
+            off(-50., 150.),
+            on(-100., 100.),
+            on(0., 0.),
+        ];
+        let mut path = Path::from_raw_parts(id, points, None, true);
+
+        assert_eq!(path.remove_stray_off_curves(), 2);
+        assert_eq!(path.points().len(), 6);
+        let mut segs = path.iter_segments();
+        assert!(matches!(segs.next().unwrap(), PathSeg::Line(..)));
+        assert!(matches!(segs.next().unwrap(), PathSeg::Line(..)));
+        assert!(matches!(segs.next().unwrap(), PathSeg::Cubic(..)));
+        assert!(matches!(segs.next().unwrap(), PathSeg::Line(..)));
+        assert!(segs.next().is_none());
+        assert_eq!(path.remove_stray_off_curves(), 0);
+    }
+
+    #[test]
+    fn close_joining_ends() {
+        let id = next_id();
+        let on = |x, y| PathPoint::on_curve(id, DPoint::new(x, y));
+        let off = |x, y| PathPoint::off_curve(id, DPoint::new(x, y));
+        let points = vec![
+            on(0., 0.),
+            on(100., 0.),
+            off(100., 50.),
+            off(50., 100.),
+            on(0., 0.),
+        ];
+        let mut path = Path::from_raw_parts(id, points, None, false);
+
+        assert!(path.close_joining_ends());
+        assert!(path.is_closed());
+        assert_eq!(path.points().len(), 4);
+        assert_eq!(path.start_point().point, DPoint::new(0., 0.));
+        assert_eq!(path.iter_segments().count(), 2);
+        assert!(!path.close_joining_ends());
+
+        let mut line = Path::new(DPoint::new(0., 0.));
+        line.append_point(DPoint::new(100., 0.));
+        assert!(line.close_joining_ends());
+        assert_eq!(line.iter_segments().count(), 2);
+    }
+
+    #[test]
+    fn append_path_welds_coincident_ends() {
+        let mut path = Path::new(DPoint::new(0., 0.));
+        let end = path.append_point(DPoint::new(100., 0.));
+        let mut other = Path::new(DPoint::new(100., 0.));
+        other.append_point(DPoint::new(100., 100.));
+
+        assert_eq!(path.clone().append_path(&other), (end, end));
+        let mut welded = path.clone();
+        welded.append_path(&other);
+        assert_eq!(welded.points().len(), 3);
+        assert!(welded.points().iter().all(|p| path.contains(&p.id)));
+
+        let mut apart = Path::new(DPoint::new(200., 0.));
+        apart.append_point(DPoint::new(200., 100.));
+        let (before, after) = path.append_path(&apart);
+        assert_eq!(before, end);
+        assert_ne!(after, end);
+        assert_eq!(path.points().len(), 4);
+        assert_eq!(path.iter_segments().count(), 3);
+    }
+
+    #[test]
+    fn nudge_points_proportionally() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (50., 100.), (100., 100.));
+        let mut path = Path::from_bezpath(bez).unwrap();
+        let end = path.points()[3].id;
+
+        // doubling the chord doubles both handles
+        path.nudge_points_proportionally(&[end], DVec2::from_raw((100., 100.)));
+        let points: Vec<_> = path.points().iter().map(|p| p.point.to_raw()).collect();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0., 0.),
+                Point::new(0., 100.),
+                Point::new(100., 200.),
+                Point::new(200., 200.),
+            ]
+        );
+    }
+
+    #[test]
+    fn g2_balances_curvature() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.curve_to((0., 50.), (40., 100.), (100., 100.));
+        bez.curve_to((200., 100.), (250., 50.), (250., 0.));
+        bez.close_path();
+
+        let mut path = Path::from_bezpath(bez).unwrap();
+        let smooth = path
+            .points()
+            .iter()
+            .find(|p| p.point == DPoint::new(100., 100.))
+            .map(|p| p.id)
+            .unwrap();
+        path.toggle_on_curve_point_type(smooth);
+
+        // the curvature at each end of the joint, up to a constant factor
+        let curvatures = |path: &Path| {
+            let pt = path.path_point_for_id(smooth).unwrap().point.to_raw();
+            let handle_in = path.prev_point(smooth);
+            let handle_out = path.next_point(smooth);
+            let far_in = path.prev_point(handle_in.id).point.to_raw();
+            let far_out = path.next_point(handle_out.id).point.to_raw();
+            let handle_in = handle_in.point.to_raw();
+            let handle_out = handle_out.point.to_raw();
+            let tangent = (handle_out - handle_in).normalize();
+            (
+                tangent.cross(far_in - pt).abs() / (pt - handle_in).hypot2(),
+                tangent.cross(far_out - pt).abs() / (handle_out - pt).hypot2(),
+            )
+        };
+
+        let (k_in, k_out) = curvatures(&path);
+        assert!((k_in - k_out).abs() / k_out > 1.0);
+        path.make_g2_continuous(&[smooth]);
+        let (k_in, k_out) = curvatures(&path);
+        assert!((k_in - k_out).abs() / k_out < 0.05);
+    }
+
+    #[test]
+    fn offset_rect() {
+        let rect = Rect::new(0., 0., 100., 100.);
+        let mut path = Path::from_bezpath(rect.to_path(0.1)).unwrap();
+        let area = path.bezier().area();
+        // make sure we're testing a counter-clockwise path
+        if area < 0.0 {
+            path.reverse_contour();
+        }
+
+        path.offset(10.);
+        let bbox = path.bezier().bounding_box();
+        assert_eq!(bbox, Rect::new(-10., -10., 110., 110.));
+
+        path.offset(-20.);
+        let bbox = path.bezier().bounding_box();
+        assert_eq!(bbox, Rect::new(10., 10., 90., 90.));
+    }
+
+    #[test]
+    fn offset_circle() {
+        let circle = Circle::new((0., 0.), 100.);
+        let mut path = Path::from_bezpath(circle.to_path(0.1)).unwrap();
+        if path.bezier().area() < 0.0 {
+            path.reverse_contour();
+        }
+        let radii = |path: &Path| -> Vec<f64> {
+            path.bezier()
+                .segments()
+                .map(|seg| seg.eval(0.5).to_vec2().hypot())
+                .collect()
+        };
+
+        path.offset(10.);
+        for radius in radii(&path) {
+            assert!((radius - 110.).abs() < 1.0, "radius {}", radius);
+        }
+
+        path.offset(-20.);
+        for radius in radii(&path) {
+            assert!((radius - 90.).abs() < 1.0, "radius {}", radius);
+        }
+    }
 }
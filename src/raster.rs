@@ -0,0 +1,132 @@
+//! A small coverage rasterizer, for previewing glyphs at text sizes.
+//!
+//! Each pixel is sampled on a regular grid, and a sample is inside the
+//! outline if its nonzero winding number is nonzero. This is slow compared
+//! to a real rasterizer, but the images are tiny.
+
+use druid::kurbo::{BezPath, PathEl, Point, Rect};
+
+/// The number of samples per pixel along each axis.
+const SUPERSAMPLE: usize = 4;
+/// The flattening tolerance, in pixels.
+const FLATTEN_TOLERANCE: f64 = 0.05;
+
+/// The coverage of each pixel in a rasterized outline, from 0.0 to 1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    pub width: usize,
+    pub height: usize,
+    values: Vec<f64>,
+}
+
+impl Coverage {
+    /// The coverage of the pixel at `col` and `row`, where row 0 is the top.
+    pub fn get(&self, col: usize, row: usize) -> f64 {
+        self.values[row * self.width + col]
+    }
+}
+
+/// Rasterize `path` on a pixel grid covering `bounds`, with `scale` pixels
+/// per design unit.
+///
+/// `bounds` is in design space (y-up); the top left of the grid is at
+/// `(bounds.x0, bounds.y1)`.
+pub fn rasterize(path: &BezPath, bounds: Rect, scale: f64) -> Coverage {
+    let width = (bounds.width() * scale).ceil().max(0.0) as usize;
+    let height = (bounds.height() * scale).ceil().max(0.0) as usize;
+    let mut values = vec![0.0; width * height];
+
+    let lines = flatten_closed(path, FLATTEN_TOLERANCE / scale);
+    let sample_step = 1.0 / (SUPERSAMPLE as f64 * scale);
+    let sample_weight = 1.0 / (SUPERSAMPLE * SUPERSAMPLE) as f64;
+    let mut crossings = Vec::new();
+
+    for sample_row in 0..height * SUPERSAMPLE {
+        let y = bounds.y1 - (sample_row as f64 + 0.5) * sample_step;
+        crossings.clear();
+        for (p0, p1) in &lines {
+            if (p0.y <= y) != (p1.y <= y) {
+                let x = p0.x + (y - p0.y) * (p1.x - p0.x) / (p1.y - p0.y);
+                let dir = if p1.y > p0.y { 1 } else { -1 };
+                crossings.push((x, dir));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let row = sample_row / SUPERSAMPLE;
+        let mut winding = 0;
+        let mut next_crossing = 0;
+        for sample_col in 0..width * SUPERSAMPLE {
+            let x = bounds.x0 + (sample_col as f64 + 0.5) * sample_step;
+            while next_crossing < crossings.len() && crossings[next_crossing].0 < x {
+                winding += crossings[next_crossing].1;
+                next_crossing += 1;
+            }
+            if winding != 0 {
+                values[row * width + sample_col / SUPERSAMPLE] += sample_weight;
+            }
+        }
+    }
+
+    Coverage {
+        width,
+        height,
+        values,
+    }
+}
+
+/// Flatten `path` to line segments, closing every subpath.
+fn flatten_closed(path: &BezPath, tolerance: f64) -> Vec<(Point, Point)> {
+    let mut lines = Vec::new();
+    let mut start = Point::ZERO;
+    let mut last = Point::ZERO;
+    path.flatten(tolerance, |el| match el {
+        PathEl::MoveTo(p) => {
+            if last != start {
+                lines.push((last, start));
+            }
+            start = p;
+            last = p;
+        }
+        PathEl::LineTo(p) => {
+            lines.push((last, p));
+            last = p;
+        }
+        PathEl::ClosePath => {
+            if last != start {
+                lines.push((last, start));
+            }
+            last = start;
+        }
+        _ => (),
+    });
+    if last != start {
+        lines.push((last, start));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasterize_rect() {
+        // a square covering the left two pixels, and half of the third
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((25.0, 0.0));
+        path.line_to((25.0, 40.0));
+        path.line_to((0.0, 40.0));
+        path.close_path();
+
+        let coverage = rasterize(&path, Rect::new(0.0, 0.0, 40.0, 40.0), 0.1);
+        assert_eq!((coverage.width, coverage.height), (4, 4));
+        for row in 0..4 {
+            assert_eq!(coverage.get(0, row), 1.0);
+            assert_eq!(coverage.get(1, row), 1.0);
+            assert_eq!(coverage.get(2, row), 0.5);
+            assert_eq!(coverage.get(3, row), 0.0);
+        }
+    }
+}
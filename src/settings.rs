@@ -0,0 +1,140 @@
+//! User-configurable behaviour.
+//!
+//! Settings are stored in the `Env`, alongside the theme, so that they are
+//! available to widgets and tools without being part of the document.
+
+use druid::{Env, Key, MouseButton};
+
+use crate::snap::{SnapConfig, SnapKind};
+
+/// If `true`, a plain paste places the geometry under the cursor; otherwise
+/// it is pasted at the coordinates it was copied from.
+pub const PASTE_AT_CURSOR: Key<bool> = Key::new("runebender.settings.paste-at-cursor");
+
+/// If `true`, double-clicking an on-curve point with the select tool toggles
+/// it between a corner and a smooth point.
+pub const DOUBLE_CLICK_TOGGLES_POINT: Key<bool> =
+    Key::new("runebender.settings.double-click-toggles-point");
+
+/// If `true`, double-clicking a guide with the select tool toggles it between
+/// horizontal and vertical.
+pub const DOUBLE_CLICK_TOGGLES_GUIDE: Key<bool> =
+    Key::new("runebender.settings.double-click-toggles-guide");
+
+/// What dragging with a mouse button does on the canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonAction {
+    /// The button's usual behaviour: the left button uses the current tool,
+    /// and the right button shows the context menu.
+    Default,
+    /// Dragging pans the canvas, whatever the current tool.
+    Pan,
+    /// With the select tool, dragging draws a selection rectangle, even if it
+    /// starts on a point or segment.
+    Marquee,
+}
+
+impl ButtonAction {
+    fn from_raw(raw: u64) -> ButtonAction {
+        match raw {
+            1 => ButtonAction::Pan,
+            2 => ButtonAction::Marquee,
+            _ => ButtonAction::Default,
+        }
+    }
+
+    fn to_raw(self) -> u64 {
+        match self {
+            ButtonAction::Default => 0,
+            ButtonAction::Pan => 1,
+            ButtonAction::Marquee => 2,
+        }
+    }
+}
+
+// the `Env` can't hold our own types, so these are read and written with
+// `button_action` and `set_button_action`.
+const LEFT_BUTTON_ACTION: Key<u64> = Key::new("runebender.settings.left-button-action");
+const MIDDLE_BUTTON_ACTION: Key<u64> = Key::new("runebender.settings.middle-button-action");
+const RIGHT_BUTTON_ACTION: Key<u64> = Key::new("runebender.settings.right-button-action");
+
+fn button_action_key(button: MouseButton) -> Option<Key<u64>> {
+    match button {
+        MouseButton::Left => Some(LEFT_BUTTON_ACTION),
+        MouseButton::Middle => Some(MIDDLE_BUTTON_ACTION),
+        MouseButton::Right => Some(RIGHT_BUTTON_ACTION),
+        _ => None,
+    }
+}
+
+/// Returns what dragging with `button` does.
+///
+/// If the right button is given another action, the context menu is not
+/// available.
+pub fn button_action(env: &Env, button: MouseButton) -> ButtonAction {
+    button_action_key(button)
+        .map(|key| ButtonAction::from_raw(env.get(key)))
+        .unwrap_or(ButtonAction::Default)
+}
+
+/// Sets what dragging with `button`, which must be the left, middle or right
+/// button, does.
+pub fn set_button_action(env: &mut Env, button: MouseButton, action: ButtonAction) {
+    match button_action_key(button) {
+        Some(key) => env.set(key, action.to_raw()),
+        None => log::warn!("{:?} can't be given an action", button),
+    }
+}
+
+/// If `true`, moving an on-curve point scales the handles of the adjacent
+/// curves with their length; otherwise the handles move rigidly with it.
+pub const PROPORTIONAL_HANDLES: Key<bool> = Key::new("runebender.settings.proportional-handles");
+
+/// The distance, in design units, that the arrow keys move the selection;
+/// Shift and Cmd multiply it by 10 and 100.
+pub const NUDGE_DISTANCE: Key<f64> = Key::new("runebender.settings.nudge-distance");
+
+/// If `true`, the arrow keys move the selection by one screen pixel at the
+/// current zoom, but never less than one design unit, instead of by
+/// `NUDGE_DISTANCE`.
+pub const NUDGE_BY_PIXEL: Key<bool> = Key::new("runebender.settings.nudge-by-pixel");
+
+/// The spacing, in design units, of the grid that moved points, handles and
+/// guides snap to when grid snapping is turned on in the View menu. The grid
+/// has lines through the origin, and only applies on an axis where no other
+/// snap target is in reach.
+pub const GRID_SPACING: Key<f64> = Key::new("runebender.settings.grid-spacing");
+
+/// The priority of snapping to on-curve points, guides and metrics when
+/// dragging. Targets with lower numbers are preferred; 0 disables a target.
+pub const SNAP_TO_POINTS: Key<u64> = Key::new("runebender.settings.snap-to-points");
+pub const SNAP_TO_GUIDES: Key<u64> = Key::new("runebender.settings.snap-to-guides");
+pub const SNAP_TO_METRICS: Key<u64> = Key::new("runebender.settings.snap-to-metrics");
+
+/// Returns the enabled snap targets, in priority order.
+pub fn snap_config(env: &Env) -> SnapConfig {
+    let mut kinds = vec![
+        (env.get(SNAP_TO_POINTS), SnapKind::Point),
+        (env.get(SNAP_TO_GUIDES), SnapKind::Guide),
+        (env.get(SNAP_TO_METRICS), SnapKind::Metric),
+    ];
+    kinds.retain(|(priority, _)| *priority > 0);
+    kinds.sort_by_key(|(priority, _)| *priority);
+    SnapConfig::new(kinds.into_iter().map(|(_, kind)| kind).collect())
+}
+
+pub fn configure_env(env: &mut Env) {
+    env.set(PASTE_AT_CURSOR, false);
+    env.set(DOUBLE_CLICK_TOGGLES_POINT, true);
+    env.set(DOUBLE_CLICK_TOGGLES_GUIDE, true);
+    set_button_action(env, MouseButton::Left, ButtonAction::Default);
+    set_button_action(env, MouseButton::Middle, ButtonAction::Pan);
+    set_button_action(env, MouseButton::Right, ButtonAction::Default);
+    env.set(PROPORTIONAL_HANDLES, false);
+    env.set(NUDGE_DISTANCE, 1.0);
+    env.set(NUDGE_BY_PIXEL, false);
+    env.set(GRID_SPACING, 10.0);
+    env.set(SNAP_TO_POINTS, 1u64);
+    env.set(SNAP_TO_GUIDES, 2u64);
+    env.set(SNAP_TO_METRICS, 3u64);
+}
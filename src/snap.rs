@@ -0,0 +1,119 @@
+//! Snapping dragged items to nearby points, guides and metrics.
+
+use std::sync::Arc;
+
+use druid::Data;
+
+use crate::design_space::DPoint;
+
+/// Distance in screen units within which a dragged item snaps to a target.
+pub const SNAP_DISTANCE: f64 = 6.0;
+
+/// The kinds of target that can be snapped to.
+#[derive(Debug, Clone, Copy, Data, PartialEq, Eq)]
+pub enum SnapKind {
+    /// On-curve points in the glyph.
+    Point,
+    /// Horizontal and vertical guides.
+    Guide,
+    /// The font's vertical metrics, and the glyph's origin and advance.
+    Metric,
+}
+
+/// The snap targets that are enabled, in priority order.
+///
+/// When targets of more than one kind are in reach, we snap to the kind that
+/// comes first in this list, even if a target of a later kind is closer.
+#[derive(Debug, Clone, Data, PartialEq)]
+pub struct SnapConfig {
+    pub priority: Arc<Vec<SnapKind>>,
+}
+
+/// A position after snapping, and what it snapped to on each axis.
+#[derive(Debug, Clone, Copy, Data, PartialEq)]
+pub struct Snapped {
+    pub point: DPoint,
+    pub x: Option<SnapKind>,
+    pub y: Option<SnapKind>,
+}
+
+impl SnapConfig {
+    pub fn new(priority: Vec<SnapKind>) -> Self {
+        SnapConfig {
+            priority: Arc::new(priority),
+        }
+    }
+}
+
+impl Default for SnapConfig {
+    fn default() -> Self {
+        SnapConfig::new(vec![SnapKind::Point, SnapKind::Guide, SnapKind::Metric])
+    }
+}
+
+impl Snapped {
+    /// A position that did not snap to anything.
+    pub fn unsnapped(point: DPoint) -> Self {
+        Snapped {
+            point,
+            x: None,
+            y: None,
+        }
+    }
+
+    pub fn is_snapped(&self) -> bool {
+        self.x.is_some() || self.y.is_some()
+    }
+}
+
+/// Snap a single coordinate.
+///
+/// `targets` are candidate positions grouped by kind, in priority order; the
+/// nearest candidate of the first kind that has one within `max_dist` wins.
+pub(crate) fn snap_axis<'a>(
+    value: f64,
+    targets: impl IntoIterator<Item = (SnapKind, &'a [f64])>,
+    max_dist: f64,
+) -> Option<(f64, SnapKind)> {
+    targets.into_iter().find_map(|(kind, candidates)| {
+        nearest_within(value, candidates, max_dist).map(|pos| (pos, kind))
+    })
+}
+
+/// The candidate nearest to `value`, if any is within `max_dist`.
+fn nearest_within(value: f64, candidates: &[f64], max_dist: f64) -> Option<f64> {
+    candidates
+        .iter()
+        .map(|c| (*c, (c - value).abs()))
+        .filter(|(_, dist)| *dist <= max_dist)
+        .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
+        .map(|(c, _)| c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_axis_prefers_earlier_kinds() {
+        let points = [103.0];
+        let guides = [101.0];
+        let targets = || {
+            vec![
+                (SnapKind::Point, &points[..]),
+                (SnapKind::Guide, &guides[..]),
+            ]
+        };
+
+        assert_eq!(
+            snap_axis(100.0, targets(), 5.0),
+            Some((103.0, SnapKind::Point))
+        );
+        // out of reach of the point, but not the guide
+        assert_eq!(
+            snap_axis(97.0, targets(), 5.0),
+            Some((101.0, SnapKind::Guide))
+        );
+        assert_eq!(snap_axis(90.0, targets(), 5.0), None);
+    }
+}
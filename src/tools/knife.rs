@@ -212,6 +212,29 @@ impl MouseDelegate<EditSession> for Knife {
         }
     }
 
+    /// A click on a segment adds a point there; with shift, it cuts the
+    /// path at that point instead.
+    fn left_click(&mut self, event: &MouseEvent, data: &mut EditSession) {
+        self.gesture = GestureState::Ready;
+        let (seg, t) = match data.hit_test_segments(event.pos, None) {
+            Some(hit) => hit,
+            None => return,
+        };
+
+        let path_idx = match data.path_idx_for_point(seg.start_id()) {
+            Some(idx) => idx,
+            None => return,
+        };
+        if event.mods.shift() {
+            let new_paths = cut_path(&data.paths[path_idx], seg, t);
+            data.selection.clear();
+            data.paths_mut().splice(path_idx..=path_idx, new_paths);
+        } else {
+            data.paths_mut()[path_idx].split_segment_at_point(seg, t);
+        }
+        self.gesture = GestureState::Finished;
+    }
+
     fn left_drag_ended(&mut self, drag: Drag, data: &mut EditSession) {
         if let GestureState::Begun { current, .. } = &mut self.gesture {
             let now = data.viewport.inverse_affine() * drag.current.pos;
@@ -418,6 +441,42 @@ fn split_path_at_intersections(path: &Path, start: Hit, end: Hit) -> (Path, Path
     (path1, path2)
 }
 
+/// Cut a path at `t` on one of its segments.
+///
+/// An open path is cut into two open paths; a closed path becomes a single
+/// open path that starts and ends at the cut.
+fn cut_path(path: &Path, cut_seg: PathSeg, t: f64) -> Vec<Path> {
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    let mut iter = path.iter_segments();
+
+    for seg in &mut iter {
+        if seg.start_id() == cut_seg.start_id() {
+            append_all_points(&mut before, seg.subsegment(0.0..t));
+            append_all_points(&mut after, seg.subsegment(t..1.0));
+            break;
+        }
+        append_all_points(&mut before, seg);
+    }
+    iter.for_each(|seg| append_all_points(&mut after, seg));
+
+    if path.is_closed() {
+        // the points before the cut now follow those after it
+        let mut iter = before.into_iter();
+        if after.last().map(|p| p.point) == iter.as_slice().first().map(|p| p.point) {
+            iter.next();
+        }
+        after.extend(iter);
+        vec![finalize_path(after, path.id(), false)]
+    } else {
+        let two_id = crate::path::next_id();
+        vec![
+            finalize_path(before, path.id(), false),
+            finalize_path(after, two_id, false),
+        ]
+    }
+}
+
 /// set tangent handles, set correct parent ids, and construct the path
 fn finalize_path(mut points: Vec<PathPoint>, parent_id: usize, closed: bool) -> Path {
     crate::path::mark_tangent_handles(&mut points);
@@ -514,6 +573,40 @@ mod tests {
         };
     }
 
+    #[test]
+    fn cut_open_and_closed_paths() {
+        let points = |path: &Path| path.points().iter().map(|p| p.point).collect::<Vec<_>>();
+
+        let mut path = Path::new(DPoint::new(0., 0.));
+        path.append_point(DPoint::new(100., 0.));
+        path.append_point(DPoint::new(100., 100.));
+        let seg = path.iter_segments().next().unwrap();
+        let out = cut_path(&path, seg, 0.5);
+        assert_eq!(out.len(), 2);
+        assert!(!out[0].is_closed() && !out[1].is_closed());
+        let expected = vec![DPoint::new(0., 0.), DPoint::new(50., 0.)];
+        assert_eq!(points(&out[0]), expected);
+        assert_eq!(out[1].points().len(), 3);
+        assert_eq!(out[1].points()[0].point, DPoint::new(50., 0.));
+
+        let mut path = Path::new(DPoint::new(10., 10.));
+        path.append_point(DPoint::new(0., 0.));
+        path.append_point(DPoint::new(20., 0.));
+        path.close();
+        let seg = path.iter_segments().nth(1).unwrap();
+        let out = cut_path(&path, seg, 0.5);
+        assert_eq!(out.len(), 1);
+        assert!(!out[0].is_closed());
+        let expected = vec![
+            DPoint::new(10., 0.),
+            DPoint::new(20., 0.),
+            DPoint::new(10., 10.),
+            DPoint::new(0., 0.),
+            DPoint::new(10., 0.),
+        ];
+        assert_eq!(points(&out[0]), expected);
+    }
+
     #[test]
     fn triangle() {
         let mut path = Path::new(DPoint::new(10., 10.));
@@ -1,6 +1,10 @@
-use druid::kurbo::{Circle, Line, ParamCurve, Point, Rect, Size, Vec2};
-use druid::piet::{Color, FontFamily, RenderContext, Text, TextLayout, TextLayoutBuilder};
-use druid::{Data, Env, EventCtx, PaintCtx};
+use druid::kurbo::{
+    Circle, Line, ParamCurve, ParamCurveDeriv, PathSeg as KurboPathSeg, Point, Rect, Size, Vec2,
+};
+use druid::piet::{
+    Color, FontFamily, RenderContext, StrokeStyle, Text, TextLayout, TextLayoutBuilder,
+};
+use druid::{Data, Env, EventCtx, MouseEvent, PaintCtx};
 
 use crate::design_space::DPoint;
 use crate::edit_session::EditSession;
@@ -10,6 +14,17 @@ use crate::tools::{EditType, Tool};
 #[derive(Default)]
 pub struct Measure {
     line: Option<Line>,
+    /// A direction picked by clicking on a segment; while shift is held,
+    /// measurements snap parallel or perpendicular to it.
+    reference: Option<Reference>,
+}
+
+/// The tangent of a segment at the point it was clicked, in design space.
+#[derive(Debug, Clone, Copy, Data)]
+struct Reference {
+    point: Point,
+    /// A unit vector.
+    dir: Vec2,
 }
 
 const MEASURE_LINE_STROKE_COLOR: Color = Color::rgb8(0x73, 0x9B, 0xCB);
@@ -20,6 +35,9 @@ const MEASURE_INFO_OFFCURVE_COLOR: Color = Color::rgb8(0x60, 0xc0, 0x60);
 const MEASURE_INFO_DELTA_COLOR: Color = Color::rgb8(0xa0, 0x20, 0x20);
 const MEASURE_INFO_FONT_SIZE: f64 = 9.0;
 const MEASURE_INTERSECTION_RADIUS: f64 = 3.0;
+const MEASURE_REFERENCE_STROKE_COLOR: Color = Color::rgb8(0xd0, 0x80, 0x40);
+// The length, in screen space, of the line drawn through the reference point.
+const MEASURE_REFERENCE_LENGTH: f64 = 10_000.0;
 
 // Don't report segments smaller than this.
 const MEASURE_FUZZY_TOLERANCE: f64 = 0.1;
@@ -67,7 +85,69 @@ fn format_pt(pt: DPoint) -> String {
     format!("{}, {}", x.trim_end_matches(".0"), y.trim_end_matches(".0"))
 }
 
+impl Reference {
+    /// The tangent of the segment nearest `pos`, if one is close enough.
+    fn for_click(data: &EditSession, pos: Point) -> Option<Reference> {
+        let (seg, t) = data.hit_test_segments(pos, None)?;
+        let kurbo_seg = seg.to_kurbo();
+        let tangent = match kurbo_seg {
+            KurboPathSeg::Line(line) => line.p1 - line.p0,
+            KurboPathSeg::Quad(quad) => quad.deriv().eval(t).to_vec2(),
+            KurboPathSeg::Cubic(cubic) => cubic.deriv().eval(t).to_vec2(),
+        };
+        // a zero-length handle has no tangent at its end; fall back to the chord
+        let tangent = if tangent.hypot() > 1e-9 {
+            tangent
+        } else {
+            kurbo_seg.end() - kurbo_seg.start()
+        };
+        if tangent.hypot() <= 1e-9 {
+            return None;
+        }
+        Some(Reference {
+            point: kurbo_seg.eval(t),
+            dir: tangent.normalize(),
+        })
+    }
+
+    /// The reference direction, in screen space, where y is down.
+    fn screen_dir(&self) -> Vec2 {
+        Vec2::new(self.dir.x, -self.dir.y)
+    }
+
+    /// Constrain the vector `delta`, in screen space, to whichever of the
+    /// parallel or perpendicular directions it is closest to.
+    fn snap(&self, delta: Vec2) -> Vec2 {
+        let parallel = self.screen_dir();
+        let perpendicular = Vec2::new(-parallel.y, parallel.x);
+        let along = delta.dot(parallel);
+        let across = delta.dot(perpendicular);
+        if along.abs() >= across.abs() {
+            parallel * along
+        } else {
+            perpendicular * across
+        }
+    }
+
+    /// The components of `delta`, in design space, parallel and perpendicular
+    /// to the reference.
+    fn components(&self, delta: Vec2) -> (f64, f64) {
+        let along = delta.dot(self.dir);
+        let across = delta.cross(self.dir);
+        (along.abs(), across.abs())
+    }
+}
+
 impl Measure {
+    /// The position of the point under `pos`, found as by the select tool,
+    /// or else `pos` itself; measurements between points are then exact.
+    fn snap_to_point(data: &EditSession, pos: Point) -> Point {
+        data.hit_test_filtered(pos, None, |_| true)
+            .and_then(|id| data.path_point_for_id(id))
+            .map(|pt| data.viewport.to_screen(pt.point))
+            .unwrap_or(pos)
+    }
+
     #[allow(clippy::float_cmp)]
     fn compute_measurement(&self, data: &EditSession, design_line: Line) -> Vec<f64> {
         // We scale the intersections to fixed point to make them easier to sort.
@@ -142,6 +222,13 @@ impl Tool for Measure {
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &EditSession, _env: &Env) {
         self.paint_coords(ctx, data);
+        if let Some(reference) = self.reference {
+            let center = data.viewport.to_screen(DPoint::from_raw(reference.point));
+            let extent = reference.screen_dir() * MEASURE_REFERENCE_LENGTH;
+            let line = Line::new(center - extent, center + extent);
+            let style = StrokeStyle::new().dash(vec![4.0, 4.0], 0.0);
+            ctx.stroke_styled(line, &MEASURE_REFERENCE_STROKE_COLOR, 1.0, &style);
+        }
         if let Some(line) = self.line {
             let angle = atan_to_angle((line.p1 - line.p0).atan2());
             let angle_offset = if angle < 90.0 {
@@ -152,14 +239,19 @@ impl Tool for Measure {
                 Vec2::new(-14.0, 8.0)
             };
             ctx.stroke(line, &MEASURE_LINE_STROKE_COLOR, 1.0);
-            let label = format!("{:.1}°", angle);
-            draw_info_bubble(ctx, line.p1 + angle_offset, label);
             // TODO: compute earlier than paint
             if let Some(line) = self.line {
                 let p0 = data.viewport.from_screen(line.p0);
                 let p1 = data.viewport.from_screen(line.p1);
                 let design_line = Line::new(p0.to_raw(), p1.to_raw());
                 let design_len = (design_line.p1 - design_line.p0).hypot();
+                let label = format!("{:.1}  {:.1}°", design_len, angle);
+                draw_info_bubble(ctx, line.p1 + angle_offset, label);
+                if let Some(reference) = self.reference {
+                    let (along, across) = reference.components(design_line.p1 - design_line.p0);
+                    let label = format!("{:.1}  ∥ {:.1}  ⊥ {:.1}", design_len, along, across);
+                    draw_info_bubble(ctx, line.p1 + angle_offset + Vec2::new(0.0, 14.0), label);
+                }
                 let intersections = self.compute_measurement(data, design_line);
                 for t in &intersections {
                     let pt = line.p0.lerp(line.p1, *t);
@@ -189,8 +281,9 @@ impl Tool for Measure {
         _: &Env,
     ) -> Option<EditType> {
         let pre_line = self.line;
+        let pre_reference = self.reference;
         mouse.mouse_event(event, data, self);
-        if !pre_line.same(&self.line) {
+        if !pre_line.same(&self.line) || !pre_reference.same(&self.reference) {
             ctx.request_paint();
         }
         None
@@ -202,17 +295,32 @@ impl MouseDelegate<EditSession> for Measure {
         self.line = None;
     }
 
-    fn left_drag_began(&mut self, drag: Drag, _data: &mut EditSession) {
-        self.line = Some(Line::new(drag.start.pos, drag.current.pos));
+    // the last measurement stays on screen until the next press
+    fn left_down(&mut self, _event: &MouseEvent, _data: &mut EditSession) {
+        self.line = None;
+    }
+
+    fn left_click(&mut self, event: &MouseEvent, data: &mut EditSession) {
+        self.reference = Reference::for_click(data, event.pos);
     }
 
-    fn left_drag_changed(&mut self, drag: Drag, _data: &mut EditSession) {
+    fn left_drag_began(&mut self, drag: Drag, data: &mut EditSession) {
+        let start = Measure::snap_to_point(data, drag.start.pos);
+        let end = Measure::snap_to_point(data, drag.current.pos);
+        self.line = Some(Line::new(start, end));
+    }
+
+    fn left_drag_changed(&mut self, drag: Drag, data: &mut EditSession) {
         if let Some(line) = &mut self.line {
-            let mut pos = drag.current.pos;
-            if drag.current.mods.shift() {
-                pos = super::axis_locked_point(pos, drag.start.pos);
-            }
-            line.p1 = pos;
+            let pos = drag.current.pos;
+            line.p1 = if drag.current.mods.shift() {
+                match self.reference {
+                    Some(reference) => line.p0 + reference.snap(pos - line.p0),
+                    None => super::axis_locked_point(pos, line.p0),
+                }
+            } else {
+                Measure::snap_to_point(data, pos)
+            };
         }
     }
 }
@@ -7,6 +7,7 @@ mod pen;
 mod preview;
 mod rectangle;
 mod select;
+mod transform;
 
 pub use ellipse::Ellipse;
 pub use knife::Knife;
@@ -15,10 +16,13 @@ pub use pen::Pen;
 pub use preview::Preview;
 pub use rectangle::Rectangle;
 pub use select::Select;
+pub use transform::Transform;
+
+use std::time::Duration;
 
 use crate::edit_session::EditSession;
 use crate::mouse::{Mouse, TaggedEvent};
-use druid::kurbo::Point;
+use druid::kurbo::{Point, Vec2};
 use druid::{Env, EventCtx, KeyEvent, PaintCtx};
 
 /// Something to pass around instead of a Box<dyn Tool>
@@ -36,17 +40,29 @@ pub type ToolId = &'static str;
 pub enum EditType {
     /// Any change that always gets its own undo group
     Normal,
+    /// Nudges in the same direction combine, as long as each follows the
+    /// last within [`COALESCE_WINDOW`], so that holding an arrow key is a
+    /// single edit.
+    ///
+    /// [`COALESCE_WINDOW`]: constant.COALESCE_WINDOW.html
     NudgeLeft,
     NudgeRight,
     NudgeUp,
     NudgeDown,
-    /// An edit where a drag of some kind is in progress.
+    /// An edit where a drag of some kind is in progress; however long the
+    /// drag takes, all of its edits combine.
     Drag,
     /// An edit that finishes a drag; it combines with the previous undo
     /// group, but not with any subsequent event.
     DragUp,
+    /// Deleting items; like `Normal`, this always gets its own undo group.
+    Delete,
 }
 
+/// The longest pause between two nudges that are still combined into a
+/// single undo group.
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
 /// A trait for representing the logic of a tool; that is, something that handles
 /// mouse and keyboard events, and modifies the current [`EditSession`].
 pub trait Tool {
@@ -125,17 +141,20 @@ pub fn tool_for_id(id: ToolId) -> Option<Box<dyn Tool>> {
         "Ellipse" => Some(Box::new(Ellipse::default())),
         "Knife" => Some(Box::new(Knife::default())),
         "Measure" => Some(Box::new(Measure::default())),
+        "Transform" => Some(Box::new(Transform::default())),
         _ => None,
     }
 }
 
 impl EditType {
-    pub fn needs_new_undo_group(self, other: EditType) -> bool {
+    /// Whether an edit of type `other`, made `elapsed` after one of this
+    /// type, starts a new undo group.
+    pub fn needs_new_undo_group(self, other: EditType, elapsed: Duration) -> bool {
         match (self, other) {
-            (EditType::NudgeDown, EditType::NudgeDown) => false,
-            (EditType::NudgeUp, EditType::NudgeUp) => false,
-            (EditType::NudgeLeft, EditType::NudgeLeft) => false,
-            (EditType::NudgeRight, EditType::NudgeRight) => false,
+            (EditType::NudgeDown, EditType::NudgeDown)
+            | (EditType::NudgeUp, EditType::NudgeUp)
+            | (EditType::NudgeLeft, EditType::NudgeLeft)
+            | (EditType::NudgeRight, EditType::NudgeRight) => elapsed > COALESCE_WINDOW,
             (EditType::Drag, EditType::Drag) => false,
             (EditType::Drag, EditType::DragUp) => false,
             _ => true,
@@ -143,6 +162,19 @@ impl EditType {
     }
 }
 
+/// Like `axis_locked_point`, but with the vertical axis following `vertical`,
+/// a unit vector in design space, such as the italic angle's upright.
+fn slant_locked_point(point: Point, prev: Point, vertical: Vec2) -> Point {
+    let vertical = Vec2::new(vertical.x, -vertical.y);
+    let delta = point - prev;
+    let along = delta.dot(vertical);
+    if delta.x.abs() > along.abs() {
+        Point::new(point.x, prev.y)
+    } else {
+        prev + vertical * along
+    }
+}
+
 /// Lock the smallest axis of `point` (from `prev`) to that axis on `prev`.
 /// (aka shift + click)
 fn axis_locked_point(point: Point, prev: Point) -> Point {
@@ -153,3 +185,22 @@ fn axis_locked_point(point: Point, prev: Point) -> Point {
         Point::new(prev.x, point.y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nudges_coalesce_within_window() {
+        let quick = Duration::from_millis(30);
+        let slow = COALESCE_WINDOW * 2;
+        assert!(!EditType::NudgeLeft.needs_new_undo_group(EditType::NudgeLeft, quick));
+        assert!(EditType::NudgeLeft.needs_new_undo_group(EditType::NudgeLeft, slow));
+        assert!(EditType::NudgeLeft.needs_new_undo_group(EditType::NudgeUp, quick));
+        // a drag is one group however slow it is, and ends with the mouse up
+        assert!(!EditType::Drag.needs_new_undo_group(EditType::Drag, slow));
+        assert!(!EditType::Drag.needs_new_undo_group(EditType::DragUp, slow));
+        assert!(EditType::DragUp.needs_new_undo_group(EditType::Drag, quick));
+        assert!(EditType::Normal.needs_new_undo_group(EditType::Normal, quick));
+    }
+}
@@ -55,7 +55,8 @@ impl MouseDelegate<EditSession> for Pen {
                 // lock to nearest vertical or horizontal axis if shift is pressed
                 Some(path) if event.mods.shift() => {
                     let last_point = path.points().last().unwrap().to_screen(vport);
-                    super::axis_locked_point(event.pos, last_point)
+                    let vertical = data.metrics.italic_vertical();
+                    super::slant_locked_point(event.pos, last_point, vertical)
                 }
                 _ => event.pos,
             };
@@ -84,7 +85,7 @@ impl MouseDelegate<EditSession> for Pen {
         }
         let Drag { start, current, .. } = drag;
         let handle_point = if current.mods.shift() {
-            super::axis_locked_point(current.pos, start.pos)
+            super::slant_locked_point(current.pos, start.pos, data.metrics.italic_vertical())
         } else {
             current.pos
         };
@@ -124,7 +125,7 @@ impl Tool for Pen {
         match event {
             e if e.key == KbKey::Backspace => {
                 data.delete_selection();
-                self.this_edit_type = Some(EditType::Normal);
+                self.this_edit_type = Some(EditType::Delete);
             }
             // TODO: should support nudging; basically a lot of this should
             // be shared with selection.
@@ -3,15 +3,76 @@
 //! This is generally represented as the 'hand', and allows the user to pan around
 //! the workspace by clicking and dragging, although whether this makes sense
 //! in the era of the touchpad is an open question.
+//!
+//! The editor also uses this tool to pan with any mouse button whose action
+//! is `settings::ButtonAction::Pan`, whatever the current tool.
+
+use druid::kurbo::Vec2;
+use druid::{Env, EventCtx};
 
-use crate::tools::{Tool, ToolId};
+use crate::consts;
+use crate::edit_session::EditSession;
+use crate::mouse::{Drag, Mouse, MouseDelegate, TaggedEvent};
+use crate::tools::{EditType, Tool, ToolId};
 
 /// The state of the preview tool.
 #[derive(Debug, Default, Clone)]
-pub struct Preview {}
+pub struct Preview {
+    /// The distance to scroll, accumulated while handling an event.
+    pan: Vec2,
+}
+
+impl Preview {
+    fn pan(&mut self, drag: Drag) {
+        // we use window coordinates, since our own move as we scroll
+        self.pan += drag.prev.window_pos - drag.current.window_pos;
+    }
+
+    /// Pans the canvas for a mouse event.
+    ///
+    /// This doesn't need the session: the viewport is updated when the
+    /// scroll is handled.
+    pub fn pan_event(&mut self, event: TaggedEvent, mouse: &mut Mouse, ctx: &mut EventCtx) {
+        mouse.mouse_event(event, &mut (), self);
+        if self.pan != Vec2::ZERO {
+            ctx.submit_command(consts::cmd::PAN_CANVAS.with(self.pan));
+            self.pan = Vec2::ZERO;
+        }
+    }
+}
 
 impl Tool for Preview {
     fn name(&self) -> ToolId {
         "Preview"
     }
+
+    fn mouse_event(
+        &mut self,
+        event: TaggedEvent,
+        mouse: &mut Mouse,
+        ctx: &mut EventCtx,
+        _: &mut EditSession,
+        _: &Env,
+    ) -> Option<EditType> {
+        self.pan_event(event, mouse, ctx);
+        None
+    }
+}
+
+impl<T> MouseDelegate<T> for Preview {
+    fn left_drag_changed(&mut self, drag: Drag, _: &mut T) {
+        self.pan(drag);
+    }
+
+    fn right_drag_changed(&mut self, drag: Drag, _: &mut T) {
+        self.pan(drag);
+    }
+
+    fn other_drag_changed(&mut self, drag: Drag, _: &mut T) {
+        self.pan(drag);
+    }
+
+    fn cancel(&mut self, _: &mut T) {
+        self.pan = Vec2::ZERO;
+    }
 }
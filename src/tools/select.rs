@@ -1,13 +1,20 @@
-use druid::kurbo::{BezPath, Circle, Insets, Point, Rect, Shape, Vec2};
-use druid::piet::{RenderContext, StrokeStyle};
-use druid::{Data, Env, EventCtx, HotKey, KbKey, KeyEvent, MouseEvent, PaintCtx, RawMods};
+use druid::kurbo::{BezPath, Circle, Insets, Line, ParamCurve, Point, Rect, Shape, Vec2};
+use druid::piet::{
+    Color, FontFamily, PietTextLayout, RenderContext, StrokeStyle, Text, TextLayout,
+    TextLayoutBuilder,
+};
+use druid::{Data, Env, EventCtx, HotKey, KbKey, KeyEvent, MouseEvent, PaintCtx, RawMods, SysMods};
 
-use crate::edit_session::EditSession;
+use crate::edit_session::{Axis, EditSession};
+use crate::guides::{Guide, GuideLine};
 use crate::mouse::{Drag, Mouse, MouseDelegate, TaggedEvent};
-use crate::path::PathSeg;
+use crate::path::{EntityId, PathSeg};
+use crate::settings::{self, ButtonAction};
+use crate::snap::Snapped;
 use crate::tools::{EditType, Tool, ToolId};
 use crate::{
     design_space::{DPoint, DVec2, ViewPort},
+    draw,
     quadrant::Quadrant,
     selection::Selection,
     theme,
@@ -16,6 +23,12 @@ use crate::{
 // distance from edges of the selection bbox to where we draw the handles
 const SELECTION_BBOX_HANDLE_PADDING: Insets = Insets::uniform(6.0);
 const SELECTION_HANDLE_RADIUS: f64 = 4.;
+const INFO_LABEL_PADDING: f64 = 4.0;
+/// With shift held, new angled guides snap to multiples of this angle, in
+/// degrees.
+const GUIDE_ANGLE_SNAP: f64 = 15.0;
+const INFO_LABEL_FONT_SIZE: f64 = 10.0;
+const INFO_LABEL_BG_COLOR: Color = Color::rgba8(0xff, 0xff, 0xff, 0x80);
 
 /// A set of states that are possible while handling a mouse drag.
 #[derive(Debug, Clone)]
@@ -27,10 +40,48 @@ enum DragState {
     },
     /// State for a drag that is moving a selected object.
     Move {
+        /// Where the drag began, in design space.
+        ///
+        /// This is fixed when the drag begins, so that the offset still
+        /// follows the cursor if the view is scrolled or zoomed mid-drag.
+        start: DPoint,
+        /// The position of the point that was grabbed, or of the cursor if
+        /// a segment was grabbed; this is what snaps to the snap targets.
+        anchor: DPoint,
         delta: DVec2,
+        /// Where the anchor last snapped, for drawing.
+        snapped: Option<Snapped>,
+        /// The session when the drag began; each step moves the selection
+        /// from here by the total delta, so that handles that are scaled
+        /// with proportional handles don't gather rounding errors.
+        previous: EditSession,
     },
     /// State for a drag that is moving an off-curve point.
-    MoveHandle,
+    MoveHandle {
+        handle: EntityId,
+        /// The on-curve point the handle belongs to, which does not move;
+        /// the handle's length and angle are shown relative to it.
+        on_curve: DPoint,
+    },
+    /// State for a drag that is moving a guide.
+    MoveGuide {
+        /// The guide as it was when the drag began.
+        original: Guide,
+        delta: DVec2,
+        /// Where the guide last snapped, for drawing.
+        snapped: Option<Snapped>,
+    },
+    /// State for a drag that is moving the advance width.
+    MoveAdvance {
+        /// The advance width when the drag began.
+        original: f64,
+    },
+    /// State for a drag that is laying down a new angled guide, through the
+    /// point where the drag began and toward the cursor.
+    NewGuide {
+        id: EntityId,
+        start: DPoint,
+    },
     /// State if some earlier gesture consumed the mouse-down, and we should not
     /// recognize a drag.
     Suppress,
@@ -59,6 +110,13 @@ pub struct Select {
     /// It is an invariant that this is always `None`, except while we are in
     /// a `key_down`, `key_up`, or `mouse_event` method.
     this_edit_type: Option<EditType>,
+    /// Whether double-clicking a point or guide toggles its type; these are
+    /// read from the `Env` at the start of each mouse event.
+    double_click_toggles_point: bool,
+    double_click_toggles_guide: bool,
+    /// `true` if the button of the current gesture is set to
+    /// `ButtonAction::Marquee`, so that a drag always draws a selection rect.
+    marquee: bool,
 }
 
 impl Tool for Select {
@@ -102,8 +160,56 @@ impl Tool for Select {
                     ctx.stroke(circle, &selection_stroke, 0.5);
                 }
             }
+            DragState::Move {
+                snapped: Some(snapped),
+                ..
+            } => draw::draw_snap_indicator(ctx, snapped, data.viewport),
+            DragState::MoveGuide {
+                original, snapped, ..
+            } => {
+                if let Some(snapped) = snapped {
+                    draw::draw_snap_indicator(ctx, snapped, data.viewport);
+                }
+                if let Some(guide) = data.guides.iter().find(|g| g.id == original.id) {
+                    let pos = guide.position();
+                    let label = match guide.guide {
+                        GuideLine::Horiz(_) => format!("y: {}", pos.y),
+                        GuideLine::Vertical(_) => format!("x: {}", pos.x),
+                        GuideLine::Angle { .. } => format!("{}, {}", pos.x, pos.y),
+                    };
+                    let layout = info_label_layout(ctx, env, label);
+                    let offset = Vec2::new(INFO_LABEL_PADDING, INFO_LABEL_PADDING);
+                    draw_info_label(ctx, &layout, self.last_pos + offset);
+                }
+            }
+            DragState::MoveAdvance { .. } => {
+                let width = data.advance_width();
+                let mut label = format!("width: {}", width);
+                if !data.paths.is_empty() {
+                    let bounds = data.to_bezier().bounding_box();
+                    let (lsb, rsb) = (bounds.x0, width - bounds.x1);
+                    label = format!("{}  lsb: {}  rsb: {}", label, lsb, rsb);
+                }
+                let layout = info_label_layout(ctx, env, label);
+                let offset = Vec2::new(INFO_LABEL_PADDING, INFO_LABEL_PADDING);
+                draw_info_label(ctx, &layout, self.last_pos + offset);
+            }
+            DragState::MoveHandle { handle, on_curve } => {
+                if let Some(pt) = data.path_point_for_id(*handle) {
+                    let vector = (pt.point - *on_curve).to_raw();
+                    let angle = vector.atan2().to_degrees();
+                    let label = format!("{:.1}  {:.1}°", vector.hypot(), angle);
+                    let layout = info_label_layout(ctx, env, label);
+                    let offset = Vec2::new(INFO_LABEL_PADDING, INFO_LABEL_PADDING);
+                    draw_info_label(ctx, &layout, self.last_pos + offset);
+                }
+            }
             _ => (),
         }
+
+        if !matches!(self.drag, DragState::Select { .. }) && data.selection.len() > 1 {
+            draw_selection_size(ctx, data, env);
+        }
     }
 
     fn key_down(
@@ -111,7 +217,7 @@ impl Tool for Select {
         event: &KeyEvent,
         _ctx: &mut EventCtx,
         data: &mut EditSession,
-        _: &Env,
+        env: &Env,
     ) -> Option<EditType> {
         assert!(self.this_edit_type.is_none());
         match event {
@@ -120,12 +226,37 @@ impl Tool for Select {
                 || e.key == KbKey::ArrowUp
                 || e.key == KbKey::ArrowRight =>
             {
-                self.nudge(data, event);
+                self.nudge(data, event, env);
             }
             e if e.key == KbKey::Backspace => {
                 data.delete_selection();
+                self.this_edit_type = Some(EditType::Delete);
+            }
+            e if HotKey::new(SysMods::CmdShift, "I").matches(e) => {
+                data.invert_selection();
                 self.this_edit_type = Some(EditType::Normal);
             }
+            // Cmd-Shift-V is paste in place, so vertical flips use Y
+            e if HotKey::new(SysMods::CmdShift, "H").matches(e) => {
+                if data.flip_selection(Axis::Horizontal) {
+                    self.this_edit_type = Some(EditType::Normal);
+                }
+            }
+            e if HotKey::new(SysMods::CmdShift, "Y").matches(e) => {
+                if data.flip_selection(Axis::Vertical) {
+                    self.this_edit_type = Some(EditType::Normal);
+                }
+            }
+            e if HotKey::new(None, "s").matches(e) => {
+                if data.toggle_selection_smooth() {
+                    self.this_edit_type = Some(EditType::Normal);
+                }
+            }
+            e if HotKey::new(None, "j").matches(e) => {
+                if data.join_selection() {
+                    self.this_edit_type = Some(EditType::Normal);
+                }
+            }
             e if HotKey::new(None, KbKey::Tab).matches(e) => data.select_next(),
             //TODO: add Shift to SysMods
             e if HotKey::new(RawMods::Shift, KbKey::Tab).matches(e) => data.select_prev(),
@@ -140,9 +271,16 @@ impl Tool for Select {
         mouse: &mut Mouse,
         ctx: &mut EventCtx,
         data: &mut EditSession,
-        _: &Env,
+        env: &Env,
     ) -> Option<EditType> {
         assert!(self.this_edit_type.is_none());
+        self.double_click_toggles_point = env.get(settings::DOUBLE_CLICK_TOGGLES_POINT);
+        self.double_click_toggles_guide = env.get(settings::DOUBLE_CLICK_TOGGLES_GUIDE);
+        if let TaggedEvent::Down(m) = &event {
+            if mouse.is_up() {
+                self.marquee = settings::button_action(env, m.button) == ButtonAction::Marquee;
+            }
+        }
         let pre_rect = self.drag.drag_rect();
         mouse.mouse_event(event, data, self);
         if !pre_rect.same(&self.drag.drag_rect()) {
@@ -157,8 +295,8 @@ impl Tool for Select {
 }
 
 impl Select {
-    fn nudge(&mut self, data: &mut EditSession, event: &KeyEvent) {
-        let (mut nudge, edit_type) = match event.key {
+    fn nudge(&mut self, data: &mut EditSession, event: &KeyEvent, env: &Env) {
+        let (direction, edit_type) = match event.key {
             KbKey::ArrowLeft => (Vec2::new(-1.0, 0.), EditType::NudgeLeft),
             KbKey::ArrowRight => (Vec2::new(1.0, 0.), EditType::NudgeRight),
             KbKey::ArrowUp => (Vec2::new(0.0, 1.0), EditType::NudgeUp),
@@ -166,22 +304,71 @@ impl Select {
             _ => unreachable!(),
         };
 
-        if event.mods.meta() {
-            nudge *= 100.;
-        } else if event.mods.shift() {
-            nudge *= 10.;
+        // with only angled guides selected, Shift-Left and Shift-Right turn
+        // them instead, by a degree, or ten with Cmd; anything else selected
+        // moves as usual, guides included
+        let is_horizontal = matches!(event.key, KbKey::ArrowLeft | KbKey::ArrowRight);
+        let only_angled_guides = !data.selection.is_empty()
+            && data.selection.iter().all(|id| {
+                data.guides
+                    .iter()
+                    .any(|g| g.id == *id && matches!(g.guide, GuideLine::Angle { .. }))
+            });
+        if is_horizontal && event.mods.shift() && only_angled_guides {
+            let degrees = if event.mods.meta() { 10.0 } else { 1.0 };
+            let degrees = if event.key == KbKey::ArrowLeft {
+                degrees
+            } else {
+                -degrees
+            };
+            data.rotate_selected_guides(degrees);
+            self.this_edit_type = if event.mods.meta() {
+                Some(EditType::Normal)
+            } else {
+                Some(edit_type)
+            };
+            return;
         }
 
-        data.nudge_selection(DVec2::from_raw(nudge));
+        let step = if let Some(spacing) = data.grid {
+            spacing
+        } else if env.get(settings::NUDGE_BY_PIXEL) {
+            (1.0 / data.viewport.zoom).round().max(1.0)
+        } else {
+            env.get(settings::NUDGE_DISTANCE)
+        };
+        let multiplier = if event.mods.meta() {
+            100.
+        } else if event.mods.shift() {
+            10.
+        } else {
+            1.
+        };
+
+        let mut delta = direction * step * multiplier;
+        if data.grid.is_some() {
+            // land on the grid, but only move along the nudged axis
+            let origin = data.selection_dpoint_bbox().origin();
+            let snapped = data.snap_to_grid(DPoint::from_raw(origin + delta)).to_raw() - origin;
+            delta = Vec2::new(snapped.x * direction.x.abs(), snapped.y * direction.y.abs());
+        }
+        data.nudge_selection(DVec2::from_raw(delta));
 
-        // for the purposes of undo, we only combine single-unit nudges
-        if nudge.hypot().abs() > 1.0 {
+        // for the purposes of undo, we only combine single-step nudges
+        if multiplier > 1.0 {
             self.this_edit_type = Some(EditType::Normal);
         } else {
             self.this_edit_type = Some(edit_type);
         }
     }
 
+    fn begin_marquee(&mut self, drag: Drag, data: &EditSession) {
+        self.drag = DragState::Select {
+            previous: data.selection.clone(),
+            rect: Rect::from_points(drag.start.pos, drag.current.pos),
+        };
+    }
+
     fn selection_handle_hit(&self, data: &EditSession, pos: Point) -> Option<Quadrant> {
         if data.selection.len() <= 1 {
             return None;
@@ -223,7 +410,7 @@ impl MouseDelegate<EditSession> for Select {
                 return;
             }
 
-            let sel = data.hit_test_all(event.pos, None);
+            let sel = hit_test(data, event.pos);
             if let Some(point_id) = sel {
                 if !event.mods.shift() {
                     // when clicking a point, if it is not selected we set it as the selection,
@@ -234,15 +421,22 @@ impl MouseDelegate<EditSession> for Select {
                 } else if !data.selection.remove(&point_id) {
                     data.selection.insert(point_id);
                 }
-            } else if let Some((seg, _t)) = data.hit_test_segments(event.pos, None) {
+            } else if let Some((seg, t)) = data.hit_test_segments(event.pos, None) {
                 let ids = seg.ids();
                 let all_selected = ids.iter().all(|id| data.selection.contains(id));
                 let append_mode = event.mods.shift();
                 self.drag = DragState::Suppress;
 
-                if event.mods.alt() && matches!(seg, PathSeg::Line(..)) {
+                // alt-clicking a line makes it a curve; alt-clicking a curve
+                // adds a point to it.
+                if event.mods.alt() {
                     let path = data.path_for_point_mut(seg.start_id()).unwrap();
-                    path.upgrade_line_seg(seg);
+                    if matches!(seg, PathSeg::Line(..)) {
+                        path.upgrade_line_seg(seg);
+                    } else {
+                        let new_point = path.split_segment_at_point(seg, t);
+                        data.selection.select_one(new_point);
+                    }
                     self.this_edit_type = Some(EditType::Normal);
                     return;
                 }
@@ -260,6 +454,14 @@ impl MouseDelegate<EditSession> for Select {
                 } else if append_mode {
                     data.selection.extend(ids);
                 }
+            } else if let Some(component) = data.hit_test_components(event.pos) {
+                if !event.mods.shift() {
+                    if !data.selection.contains(&component) {
+                        data.selection.select_one(component);
+                    }
+                } else if !data.selection.remove(&component) {
+                    data.selection.insert(component);
+                }
             } else if !event.mods.shift() {
                 data.selection.clear();
             }
@@ -267,15 +469,16 @@ impl MouseDelegate<EditSession> for Select {
             let sel = data.hit_test_all(event.pos, None);
             match sel {
                 Some(id)
-                    if data
-                        .path_point_for_id(id)
-                        .map(|p| p.is_on_curve())
-                        .unwrap_or(false) =>
+                    if self.double_click_toggles_point
+                        && data
+                            .path_point_for_id(id)
+                            .map(|p| p.is_on_curve())
+                            .unwrap_or(false) =>
                 {
                     data.toggle_selected_on_curve_type();
                     self.this_edit_type = Some(EditType::Normal);
                 }
-                Some(id) if id.is_guide() => {
+                Some(id) if self.double_click_toggles_guide && id.is_guide() => {
                     data.toggle_guide(id, event.pos);
                     self.this_edit_type = Some(EditType::Normal);
                 }
@@ -294,6 +497,10 @@ impl MouseDelegate<EditSession> for Select {
         if matches!(self.drag, DragState::Suppress) {
             return;
         }
+        if self.marquee {
+            self.begin_marquee(drag, data);
+            return;
+        }
 
         // are we dragging a selection rect handle?
         if let Some(quadrant) = self.selection_handle_hit(data, drag.start.pos) {
@@ -307,24 +514,74 @@ impl MouseDelegate<EditSession> for Select {
             return;
         }
 
+        let sel = hit_test(data, drag.start.pos);
+        if sel.map(EntityId::is_advance_width).unwrap_or(false) {
+            self.drag = DragState::MoveAdvance {
+                original: data.advance_width(),
+            };
+            return;
+        }
+        if let Some(guide) = sel
+            .filter(|id| id.is_guide())
+            .and_then(|id| data.guides.iter().find(|g| g.id == id))
+        {
+            self.drag = DragState::MoveGuide {
+                original: guide.clone(),
+                delta: DVec2::ZERO,
+                snapped: None,
+            };
+            return;
+        }
+
+        let start = data.viewport.from_screen(drag.start.pos);
+        let previous = data.clone();
+        let move_drag = move |anchor| DragState::Move {
+            start,
+            anchor,
+            delta: DVec2::ZERO,
+            snapped: None,
+            previous,
+        };
         // if we're starting a rectangular selection, we save the previous selection
-        let sel = data.hit_test_all(drag.start.pos, None);
         self.drag = if let Some(pt) = sel.and_then(|id| data.path_point_for_id(id)) {
             let is_handle = !pt.is_on_curve();
             let is_dragging_handle = data.selection.len() == 1 && is_handle;
             if is_dragging_handle {
-                DragState::MoveHandle
+                DragState::MoveHandle {
+                    handle: pt.id,
+                    on_curve: on_curve_for_handle(data, pt.id).unwrap_or(pt.point),
+                }
             } else {
-                DragState::Move { delta: DVec2::ZERO }
+                move_drag(pt.point)
             }
+        } else if let Some(anchor) = sel.and_then(|id| data.anchors.iter().find(|a| a.id == id)) {
+            move_drag(anchor.point)
         } else if data.hit_test_segments(drag.start.pos, None).is_some() {
-            DragState::Move { delta: DVec2::ZERO }
+            move_drag(start)
+        } else if data.hit_test_components(drag.start.pos).is_some() {
+            move_drag(start)
+        } else if drag.start.mods.alt() {
+            DragState::NewGuide {
+                id: data.add_angled_guide(start, 0.0),
+                start,
+            }
         } else {
             // if we're starting a rectangular selection, we save the previous selection
             DragState::Select {
                 previous: data.selection.clone(),
                 rect: Rect::from_points(drag.start.pos, drag.current.pos),
             }
+        };
+
+        // alt-dragging moves a copy, leaving the original in place. This is
+        // reported as part of the drag, so that a single undo removes the
+        // copy along with the move.
+        if let DragState::Move { previous, .. } = &mut self.drag {
+            if drag.start.mods.alt() {
+                data.duplicate_selection();
+                *previous = data.clone();
+                self.this_edit_type = Some(EditType::Drag);
+            }
         }
     }
 
@@ -333,21 +590,69 @@ impl MouseDelegate<EditSession> for Select {
         match &mut self.drag {
             DragState::Select { previous, rect } => {
                 *rect = Rect::from_points(drag.current.pos, drag.start.pos);
-                update_selection_for_drag(data, previous, *rect, drag.current.mods.shift());
+                // dragging right-to-left also selects segments the rect crosses
+                let crossing = drag.current.pos.x < drag.start.pos.x;
+                let shift = drag.current.mods.shift();
+                update_selection_for_drag(data, previous, *rect, shift, crossing);
             }
-            DragState::Move { delta } => {
-                let mut new_delta = delta_for_drag_change(&drag, data.viewport);
+            DragState::Move {
+                start,
+                anchor,
+                delta,
+                snapped,
+                previous,
+            } => {
+                let mut new_delta = data.viewport.from_screen(drag.current.pos) - *start;
+                *snapped = None;
                 if drag.current.mods.shift() {
-                    new_delta = new_delta.axis_locked();
+                    new_delta = new_delta.axis_locked_to(data.metrics.italic_vertical());
+                    new_delta = data.snap_to_grid(*anchor + new_delta) - *anchor;
+                } else {
+                    // the selection is moving, so it can't be a target
+                    let snap = data.snap_position(*anchor + new_delta, &data.selection);
+                    new_delta = snap.point - *anchor;
+                    *snapped = Some(snap).filter(Snapped::is_snapped);
+                }
+                if new_delta != *delta {
+                    let mut new_data = previous.clone();
+                    // the view may have been scrolled or zoomed mid-drag
+                    new_data.viewport = data.viewport;
+                    new_data.nudge_selection(new_delta);
+                    *data = new_data;
+                    *delta = new_delta;
                 }
+            }
+            DragState::MoveHandle { .. } => {
+                data.update_handle(drag.current.pos, drag.current.mods.shift());
+            }
+            DragState::MoveGuide {
+                original,
+                delta,
+                snapped,
+            } => {
+                let new_delta = delta_for_drag_change(&drag, data.viewport);
+                let (new_delta, snap) = data.snap_guide_delta(original, new_delta);
+                *snapped = Some(snap);
                 let drag_delta = new_delta - *delta;
                 if drag_delta.hypot() > 0. {
-                    data.nudge_selection(drag_delta);
+                    data.move_guide(original.id, drag_delta);
                     *delta = new_delta;
                 }
             }
-            DragState::MoveHandle => {
-                data.update_handle(drag.current.pos, drag.current.mods.shift());
+            DragState::MoveAdvance { original } => {
+                let delta = delta_for_drag_change(&drag, data.viewport);
+                let width = DPoint::from_raw((*original + delta.x, 0.0));
+                data.set_advance_width(data.snap_to_grid(width).x);
+            }
+            DragState::NewGuide { id, start } => {
+                let delta = data.viewport.from_screen(drag.current.pos) - *start;
+                if delta.hypot() > 0. {
+                    let mut degrees = delta.to_raw().atan2().to_degrees();
+                    if drag.current.mods.shift() {
+                        degrees = (degrees / GUIDE_ANGLE_SNAP).round() * GUIDE_ANGLE_SNAP;
+                    }
+                    data.set_guide_angle(*id, *start, degrees);
+                }
             }
             DragState::Suppress => (),
             DragState::TransformSelection {
@@ -382,14 +687,70 @@ impl MouseDelegate<EditSession> for Select {
         }
     }
 
+    fn right_up(&mut self, _event: &MouseEvent, _data: &mut EditSession) {
+        self.drag = DragState::None;
+    }
+
+    fn right_drag_began(&mut self, drag: Drag, data: &mut EditSession) {
+        if self.marquee {
+            self.begin_marquee(drag, data);
+        }
+    }
+
+    fn right_drag_changed(&mut self, drag: Drag, data: &mut EditSession) {
+        if matches!(self.drag, DragState::Select { .. }) {
+            self.left_drag_changed(drag, data);
+        }
+    }
+
+    fn other_up(&mut self, event: &MouseEvent, data: &mut EditSession) {
+        self.right_up(event, data);
+    }
+
+    fn other_drag_began(&mut self, drag: Drag, data: &mut EditSession) {
+        self.right_drag_began(drag, data);
+    }
+
+    fn other_drag_changed(&mut self, drag: Drag, data: &mut EditSession) {
+        self.right_drag_changed(drag, data);
+    }
+
     fn cancel(&mut self, data: &mut EditSession) {
         let old_state = std::mem::replace(&mut self.drag, DragState::None);
-        if let DragState::Select { previous, .. } = old_state {
-            data.selection = previous;
+        match old_state {
+            DragState::Select { previous, .. } => data.selection = previous,
+            DragState::NewGuide { id, .. } => {
+                data.guides_mut().retain(|g| g.id != id);
+                data.selection.clear();
+            }
+            _ => (),
         }
     }
 }
 
+/// Hit test points, guides and the advance, preferring segments to the
+/// advance where they overlap.
+fn hit_test(data: &EditSession, pos: Point) -> Option<EntityId> {
+    let sel = data.hit_test_all(pos, None);
+    if sel.map(EntityId::is_advance_width).unwrap_or(false)
+        && data.hit_test_segments(pos, None).is_some()
+    {
+        return None;
+    }
+    sel
+}
+
+/// The position of the on-curve point that this handle belongs to.
+fn on_curve_for_handle(data: &EditSession, handle: EntityId) -> Option<DPoint> {
+    let path = data.path_for_point(handle)?;
+    let prev = path.prev_point(handle);
+    if prev.is_on_curve() {
+        Some(prev.point)
+    } else {
+        Some(path.next_point(handle).point)
+    }
+}
+
 /// When dragging, we only update positions when they change in design-space,
 /// so we keep track of the current total design-space delta.
 fn delta_for_drag_change(drag: &Drag, viewport: ViewPort) -> DVec2 {
@@ -398,7 +759,42 @@ fn delta_for_drag_change(drag: &Drag, viewport: ViewPort) -> DVec2 {
     drag_pos - drag_start
 }
 
-fn iter_handle_circles(session: &EditSession) -> impl Iterator<Item = (Quadrant, Circle)> {
+/// Draw the width and height of the selection's bounding box, in design units,
+/// just below its bottom right corner.
+fn draw_selection_size(ctx: &mut PaintCtx, session: &EditSession, env: &Env) {
+    let bounds = match session.selection_bounds() {
+        Some(bounds) => bounds,
+        None => return,
+    };
+    let label = format!("{} × {}", bounds.width(), bounds.height());
+    let bbox = session.viewport.rect_to_screen(bounds) + SELECTION_BBOX_HANDLE_PADDING;
+    let layout = info_label_layout(ctx, env, label);
+    let text_pos = Point::new(bbox.x1 - layout.size().width, bbox.y1 + INFO_LABEL_PADDING);
+    draw_info_label(ctx, &layout, text_pos);
+}
+
+fn info_label_layout(ctx: &mut PaintCtx, env: &Env, label: String) -> PietTextLayout {
+    ctx.text()
+        .new_text_layout(label)
+        .font(FontFamily::SYSTEM_UI, INFO_LABEL_FONT_SIZE)
+        .text_color(env.get(theme::SELECTION_RECT_STROKE_COLOR))
+        .build()
+        .unwrap()
+}
+
+/// Draw a text layout over a translucent background, so it stays legible over
+/// the glyph.
+fn draw_info_label(ctx: &mut PaintCtx, layout: &PietTextLayout, pos: Point) {
+    let bg = Rect::from_origin_size(pos, layout.size())
+        .inset(2.0)
+        .to_rounded_rect(2.0);
+    ctx.fill(bg, &INFO_LABEL_BG_COLOR);
+    ctx.draw_text(layout, pos);
+}
+
+pub(super) fn iter_handle_circles(
+    session: &EditSession,
+) -> impl Iterator<Item = (Quadrant, Circle)> {
     let bbox = session
         .viewport
         .rect_to_screen(session.selection_dpoint_bbox());
@@ -417,22 +813,60 @@ fn iter_handle_circles(session: &EditSession) -> impl Iterator<Item = (Quadrant,
         })
 }
 
+/// Update the selection for a rectangular selection drag.
+///
+/// Points inside `rect` are selected; if `crossing` is true, so are the ends
+/// of any segment that passes through it.
 fn update_selection_for_drag(
     data: &mut EditSession,
     prev_sel: &Selection,
     rect: Rect,
     shift: bool,
+    crossing: bool,
 ) {
-    let in_select_rect = data
+    let mut in_select_rect: Selection = data
         .iter_points()
+        .filter(|p| p.is_on_curve() || !data.hide_handles)
         .filter(|p| rect.contains(p.to_screen(data.viewport)))
         .map(|p| p.id)
         .collect();
-    data.selection = if shift {
+    if crossing {
+        let inverse = data.viewport.inverse_affine();
+        let design_rect = Rect::from_points(
+            inverse * rect.origin(),
+            inverse * Point::new(rect.x1, rect.y1),
+        );
+        for seg in data.paths.iter().flat_map(|path| path.iter_segments()) {
+            if segment_intersects_rect(&seg, design_rect) {
+                in_select_rect.insert(seg.start_id());
+                in_select_rect.insert(seg.end_id());
+            }
+        }
+    }
+    let selection = if shift {
         prev_sel.symmetric_difference(&in_select_rect)
     } else {
         prev_sel.union(&in_select_rect)
     };
+    data.set_selection(selection.iter().copied());
+}
+
+/// Returns `true` if any part of the segment is inside `rect`.
+fn segment_intersects_rect(seg: &PathSeg, rect: Rect) -> bool {
+    let seg = seg.to_kurbo();
+    if rect.contains(seg.start()) {
+        return true;
+    }
+    let corners = [
+        Point::new(rect.x0, rect.y0),
+        Point::new(rect.x1, rect.y0),
+        Point::new(rect.x1, rect.y1),
+        Point::new(rect.x0, rect.y1),
+    ];
+    (0..4).any(|i| {
+        let edge = Line::new(corners[i], corners[(i + 1) % 4]);
+        !seg.intersect_line(edge).is_empty()
+    })
 }
 
 impl Default for DragState {
@@ -451,10 +885,44 @@ impl DragState {
     }
 
     fn is_move(&self) -> bool {
-        matches!(self, DragState::Move { .. })
+        matches!(
+            self,
+            DragState::Move { .. }
+                | DragState::MoveHandle { .. }
+                | DragState::MoveGuide { .. }
+                | DragState::MoveAdvance { .. }
+                | DragState::NewGuide { .. }
+        )
     }
 
     fn is_transform(&self) -> bool {
         matches!(self, DragState::TransformSelection{ .. })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{next_id, PathPoint};
+
+    #[test]
+    fn segments_crossing_rect() {
+        let id = next_id();
+        let on = |x, y| PathPoint::on_curve(id, DPoint::new(x, y));
+        let off = |x, y| PathPoint::off_curve(id, DPoint::new(x, y));
+        let line = PathSeg::Line(on(0., 0.), on(100., 0.));
+        let curve = PathSeg::Cubic(on(0., 0.), off(0., 100.), off(100., 100.), on(100., 0.));
+
+        // crosses the middle of both, without containing an end point
+        let rect = Rect::new(40., -10., 60., 10.);
+        assert!(segment_intersects_rect(&line, rect));
+        assert!(!segment_intersects_rect(&curve, rect));
+        // only the curve reaches this high
+        let rect = Rect::new(40., 50., 60., 80.);
+        assert!(!segment_intersects_rect(&line, rect));
+        assert!(segment_intersects_rect(&curve, rect));
+        // entirely inside
+        let rect = Rect::new(-10., -10., 110., 110.);
+        assert!(segment_intersects_rect(&curve, rect));
+    }
+}
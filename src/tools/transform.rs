@@ -0,0 +1,181 @@
+//! The transform tool, for scaling and rotating a selection.
+
+use druid::kurbo::{Affine, Circle, Point, Vec2};
+use druid::piet::{RenderContext, StrokeStyle};
+use druid::{Env, EventCtx, PaintCtx};
+
+use crate::design_space::DPoint;
+use crate::edit_session::EditSession;
+use crate::mouse::{Drag, Mouse, MouseDelegate, TaggedEvent};
+use crate::quadrant::Quadrant;
+use crate::theme;
+use crate::tools::select::iter_handle_circles;
+use crate::tools::{EditType, Tool};
+
+/// How far outside a corner handle, in screen units, a drag rotates the
+/// selection.
+const ROTATE_HANDLE_REACH: f64 = 16.0;
+/// With shift held, rotation snaps to multiples of this angle.
+const ROTATE_SNAP_ANGLE: f64 = std::f64::consts::PI / 12.0;
+
+/// The state of the transform tool.
+#[derive(Debug, Default, Clone)]
+pub struct Transform {
+    gesture: Gesture,
+    /// The edit type produced by the current event, if any.
+    this_edit_type: Option<EditType>,
+}
+
+#[derive(Debug, Clone)]
+enum Gesture {
+    Ready,
+    /// Dragging a handle scales the selection about the opposite handle.
+    Scale {
+        handle: Quadrant,
+        previous: EditSession,
+    },
+    /// Dragging near a corner rotates the selection about its center.
+    Rotate {
+        center: Point,
+        start_angle: f64,
+        previous: EditSession,
+    },
+}
+
+impl Default for Gesture {
+    fn default() -> Self {
+        Gesture::Ready
+    }
+}
+
+impl Transform {
+    /// The transform for the current drag, in design space.
+    fn affine_for_drag(&self, drag: &Drag, data: &EditSession) -> Option<Affine> {
+        let current = data.viewport.from_screen(drag.current.pos).to_raw();
+        match &self.gesture {
+            Gesture::Ready => None,
+            Gesture::Scale { handle, previous } => {
+                let rect = previous.selection_dpoint_bbox();
+                let start = data.viewport.from_screen(drag.start.pos);
+                let delta = handle.lock_delta(DPoint::from_raw(current) - start);
+                let mut scale = handle.scale_dspace_rect(rect, delta);
+                let is_corner = handle.modifies_x_axis() && handle.modifies_y_axis();
+                if drag.current.mods.shift() && is_corner {
+                    let uniform = scale.x.abs().max(scale.y.abs());
+                    scale = Vec2::new(uniform * scale.x.signum(), uniform * scale.y.signum());
+                }
+                let anchor = handle.inverse().point_in_dspace_rect(rect).to_vec2();
+                Some(
+                    Affine::translate(anchor)
+                        * Affine::scale_non_uniform(scale.x, scale.y)
+                        * Affine::translate(-anchor),
+                )
+            }
+            Gesture::Rotate {
+                center,
+                start_angle,
+                ..
+            } => {
+                let mut angle = (current - *center).atan2() - start_angle;
+                if drag.current.mods.shift() {
+                    angle = (angle / ROTATE_SNAP_ANGLE).round() * ROTATE_SNAP_ANGLE;
+                }
+                let center = center.to_vec2();
+                Some(Affine::translate(center) * Affine::rotate(angle) * Affine::translate(-center))
+            }
+        }
+    }
+}
+
+impl Tool for Transform {
+    fn name(&self) -> &'static str {
+        "Transform"
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &EditSession, env: &Env) {
+        if data.selection.len() < 2 {
+            return;
+        }
+        let stroke = env.get(theme::SELECTION_RECT_STROKE_COLOR);
+        let bbox = data.viewport.rect_to_screen(data.selection_dpoint_bbox());
+        let style = StrokeStyle::new().dash(vec![2.0, 4.0], 0.0);
+        ctx.stroke_styled(&bbox, &stroke, 0.5, &style);
+        for (_, circle) in iter_handle_circles(data) {
+            ctx.stroke(circle, &stroke, 0.5);
+        }
+    }
+
+    fn mouse_event(
+        &mut self,
+        event: TaggedEvent,
+        mouse: &mut Mouse,
+        _ctx: &mut EventCtx,
+        data: &mut EditSession,
+        _: &Env,
+    ) -> Option<EditType> {
+        assert!(self.this_edit_type.is_none());
+        mouse.mouse_event(event, data, self);
+        self.this_edit_type.take()
+    }
+}
+
+impl MouseDelegate<EditSession> for Transform {
+    fn cancel(&mut self, data: &mut EditSession) {
+        let old_gesture = std::mem::replace(&mut self.gesture, Gesture::Ready);
+        match old_gesture {
+            Gesture::Scale { previous, .. } | Gesture::Rotate { previous, .. } => *data = previous,
+            Gesture::Ready => (),
+        }
+    }
+
+    fn left_drag_began(&mut self, drag: Drag, data: &mut EditSession) {
+        if data.selection.len() < 2 {
+            return;
+        }
+        let handles: Vec<(Quadrant, Circle)> = iter_handle_circles(data).collect();
+        if let Some((handle, _)) = handles.iter().find(|(_, c)| c.contains(drag.start.pos)) {
+            self.gesture = Gesture::Scale {
+                handle: *handle,
+                previous: data.clone(),
+            };
+            return;
+        }
+
+        let bbox = data.viewport.rect_to_screen(data.selection_dpoint_bbox());
+        let near_corner = handles.iter().any(|(handle, circle)| {
+            let is_corner = handle.modifies_x_axis() && handle.modifies_y_axis();
+            is_corner && circle.center.distance(drag.start.pos) <= ROTATE_HANDLE_REACH
+        });
+        if near_corner && !bbox.contains(drag.start.pos) {
+            let center = data.selection_dpoint_bbox().center();
+            let start = data.viewport.from_screen(drag.start.pos).to_raw();
+            self.gesture = Gesture::Rotate {
+                center,
+                start_angle: (start - center).atan2(),
+                previous: data.clone(),
+            };
+        }
+    }
+
+    fn left_drag_changed(&mut self, drag: Drag, data: &mut EditSession) {
+        let affine = match self.affine_for_drag(&drag, data) {
+            Some(affine) => affine,
+            None => return,
+        };
+        let previous = match &self.gesture {
+            Gesture::Scale { previous, .. } | Gesture::Rotate { previous, .. } => previous,
+            Gesture::Ready => return,
+        };
+        let mut new_data = previous.clone();
+        new_data.transform_selection(affine);
+        *data = new_data;
+        self.this_edit_type = Some(EditType::Drag);
+    }
+
+    fn left_drag_ended(&mut self, _drag: Drag, _data: &mut EditSession) {
+        if !matches!(self.gesture, Gesture::Ready) {
+            self.this_edit_type = Some(EditType::DragUp);
+        }
+        self.gesture = Gesture::Ready;
+    }
+}
@@ -59,6 +59,18 @@ impl<T> UndoState<T> {
         }
     }
 
+    /// The current state.
+    pub(crate) fn current(&self) -> &T {
+        &self.stack[self.live_index]
+    }
+
+    /// The state before the current one, if there is one.
+    pub(crate) fn previous(&self) -> Option<&T> {
+        self.live_index
+            .checked_sub(1)
+            .and_then(|idx| self.stack.get(idx))
+    }
+
     /// Modify the state for the currently active undo group.
     /// This might be done if an edit occurs that combines with the previous undo,
     /// or if we want to save selection state.
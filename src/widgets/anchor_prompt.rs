@@ -0,0 +1,45 @@
+//! A prompt for naming a new anchor, or renaming an existing one.
+//!
+//! This is intended to be shown as a modal panel in the editor window.
+
+use druid::Widget;
+
+use crate::consts::{self, cmd::AnchorTarget};
+use crate::data::EditorState;
+use crate::widgets::prompt::text_prompt;
+
+/// A prompt for the name of `target`; `name` is the name it starts with.
+pub fn anchor_prompt(target: AnchorTarget, name: String) -> impl Widget<EditorState> {
+    text_prompt(
+        "Anchor name:",
+        "top",
+        "OK",
+        name,
+        move |name, data| validate(target, name, data),
+        move |ctx, name| {
+            let args = consts::cmd::SetAnchorNameArgs { target, name };
+            ctx.submit_command(consts::cmd::SET_ANCHOR_NAME.with(args));
+        },
+    )
+}
+
+/// Returns the name to use, or an explanation of why it can't be used.
+fn validate(target: AnchorTarget, name: &str, data: &EditorState) -> Result<String, String> {
+    if name.is_empty() {
+        return Err(String::new());
+    }
+    let renamed = match target {
+        AnchorTarget::Existing(id) => Some(id),
+        AnchorTarget::New(_) => None,
+    };
+    let is_taken = data
+        .session
+        .anchors
+        .iter()
+        .any(|a| a.name == name && Some(a.id) != renamed);
+    if is_taken {
+        Err(format!("there is already an anchor named '{}'", name))
+    } else {
+        Ok(name.to_string())
+    }
+}
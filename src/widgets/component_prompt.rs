@@ -0,0 +1,40 @@
+//! A prompt for choosing a glyph to add the current glyph to, as a component.
+//!
+//! This is intended to be shown as a modal panel in the editor window.
+
+use druid::Widget;
+use norad::GlyphName;
+
+use crate::consts;
+use crate::data::EditorState;
+use crate::widgets::prompt::text_prompt;
+
+pub fn component_prompt() -> impl Widget<EditorState> {
+    text_prompt(
+        "Use as component in glyph:",
+        "glyph name",
+        "Add",
+        String::new(),
+        validate,
+        |ctx, args| ctx.submit_command(consts::cmd::USE_AS_COMPONENT.with(args)),
+    )
+}
+
+/// Returns the arguments for adding the current glyph to the glyph `name`,
+/// or an explanation of why the name can't be used.
+fn validate(name: &str, data: &EditorState) -> Result<consts::cmd::UseAsComponentArgs, String> {
+    if name.is_empty() {
+        return Err(String::new());
+    }
+    let target: GlyphName = name.into();
+    if data.font.glyph(&target).is_none() {
+        Err(format!("no glyph named '{}'", target))
+    } else if target == data.session.name || data.font.uses_component(&data.session.name, &target) {
+        Err(format!("'{}' can't contain this glyph", target))
+    } else {
+        Ok(consts::cmd::UseAsComponentArgs {
+            base: data.session.name.clone(),
+            target,
+        })
+    }
+}
@@ -7,7 +7,7 @@ use crate::consts;
 use crate::data::{AppState, EditorState};
 use crate::edit_session::EditSession;
 use crate::menus;
-use crate::widgets::{CoordPane, FloatingPanel, GlyphPane, Toolbar};
+use crate::widgets::{pixel_preview, CoordPane, FloatingPanel, GlyphPane, Toolbar};
 
 /// the distance from the edge of a floating panel to the edge of the window.
 const FLOATING_PANEL_PADDING: f64 = 20.0;
@@ -57,6 +57,8 @@ pub struct EditorController<W> {
     toolbar: WidgetPod<(), FloatingPanel<Toolbar>>,
     coord_panel: WidgetPod<EditorState, FloatingPanel<Box<dyn Widget<EditorState>>>>,
     glyph_panel: WidgetPod<EditorState, FloatingPanel<Box<dyn Widget<EditorState>>>>,
+    pixel_panel: WidgetPod<EditorState, FloatingPanel<Box<dyn Widget<EditorState>>>>,
+    show_pixel_preview: bool,
 }
 
 impl<W> EditorController<W> {
@@ -70,6 +72,8 @@ impl<W> EditorController<W> {
                     .boxed(),
             )),
             glyph_panel: WidgetPod::new(FloatingPanel::new(GlyphPane::new().boxed())),
+            pixel_panel: WidgetPod::new(FloatingPanel::new(pixel_preview().boxed())),
+            show_pixel_preview: false,
         }
     }
 }
@@ -86,9 +90,20 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
                 return;
             }
         }
+        if let Event::Command(cmd) = event {
+            if cmd.is(consts::cmd::TOGGLE_PIXEL_PREVIEW) {
+                self.show_pixel_preview = !self.show_pixel_preview;
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+        }
         self.toolbar.event(ctx, event, &mut (), env);
         self.coord_panel.event(ctx, event, data, env);
         self.glyph_panel.event(ctx, event, data, env);
+        if self.show_pixel_preview {
+            self.pixel_panel.event(ctx, event, data, env);
+        }
         if !ctx.is_handled() {
             self.inner.event(ctx, event, data, env);
         }
@@ -111,6 +126,7 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         self.toolbar.lifecycle(ctx, event, &(), env);
         self.coord_panel.lifecycle(ctx, event, data, env);
         self.glyph_panel.lifecycle(ctx, event, data, env);
+        self.pixel_panel.lifecycle(ctx, event, data, env);
         self.inner.lifecycle(ctx, event, data, env);
     }
 
@@ -123,6 +139,7 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
     ) {
         self.coord_panel.update(ctx, data, env);
         self.glyph_panel.update(ctx, data, env);
+        self.pixel_panel.update(ctx, data, env);
         self.inner.update(ctx, old_data, data, env);
     }
 
@@ -155,6 +172,14 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         );
         let frame = Rect::from_origin_size(orig, size);
         self.glyph_panel.set_layout_rect(ctx, data, env, frame);
+
+        let size = self.pixel_panel.layout(ctx, &child_bc, data, env);
+        let orig = (
+            our_size.width - size.width - FLOATING_PANEL_PADDING,
+            FLOATING_PANEL_PADDING,
+        );
+        let frame = Rect::from_origin_size(orig, size);
+        self.pixel_panel.set_layout_rect(ctx, data, env, frame);
         our_size
     }
 
@@ -162,6 +187,9 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         self.inner.paint(ctx, data, env);
         self.coord_panel.paint(ctx, data, env);
         self.glyph_panel.paint(ctx, data, env);
+        if self.show_pixel_preview {
+            self.pixel_panel.paint(ctx, data, env);
+        }
         self.toolbar.paint(ctx, &(), env);
     }
 }
@@ -37,7 +37,12 @@ impl<W: Widget<CoordinateSelection>> Controller<CoordinateSelection, W> for Coor
 
         // if another edit has occured in the coordpanel, we turn it into
         // a command so that the Editor can update undo state:
-        if child_data.frame.origin() != data.frame.origin() {
+        if child_data.overshoot != data.overshoot {
+            if let Some((metric, value)) = child_data.overshoot {
+                let args = consts::cmd::SetOvershootArgs { metric, value };
+                ctx.submit_command(consts::cmd::SET_SELECTION_OVERSHOOT.with(args));
+            }
+        } else if child_data.frame.origin() != data.frame.origin() {
             let delta = child_data.frame.origin() - data.frame.origin();
             ctx.submit_command(consts::cmd::NUDGE_SELECTION.with(DVec2::from_raw(delta)));
         } else if child_data.frame.size() != data.frame.size() {
@@ -181,7 +186,7 @@ fn build_widget() -> impl Widget<CoordinateSelection> {
                 Flex::row()
                     .with_child(
                         Label::new("h")
-                            .with_font(coord_label_font)
+                            .with_font(coord_label_font.clone())
                             .with_text_color(theme::SECONDARY_TEXT_COLOR),
                     )
                     .with_spacer(4.0)
@@ -196,11 +201,52 @@ fn build_widget() -> impl Widget<CoordinateSelection> {
         SizedBox::empty(),
     );
 
-    let picker_and_editor = Flex::row()
-        .with_child(coord_picker)
-        .with_child(coord_editor)
-        .with_child(bbox_info)
-        .padding(4.0);
+    let overshoot_lens = druid::lens::Map::new(
+        |d: &CoordinateSelection| d.overshoot.map(|(_, dist)| dist).unwrap_or_default(),
+        |d: &mut CoordinateSelection, dist| {
+            if let Some(overshoot) = d.overshoot.as_mut() {
+                overshoot.1 = dist;
+            }
+        },
+    );
+
+    let overshoot_info = Either::new(
+        |d: &CoordinateSelection, _| d.overshoot.is_some(),
+        Flex::row()
+            .cross_axis_alignment(CrossAxisAlignment::Baseline)
+            .with_child(
+                Label::new("o")
+                    .with_font(coord_label_font)
+                    .with_text_color(theme::SECONDARY_TEXT_COLOR),
+            )
+            .with_child(
+                EditableLabel::parse()
+                    .with_font(theme::UI_DETAIL_FONT)
+                    .lens(overshoot_lens)
+                    .fix_width(40.0),
+            )
+            .with_child(
+                Label::dynamic(|d: &CoordinateSelection, _| match d.overshoot {
+                    Some((metric, _)) => format!("from {}", metric.name()),
+                    None => String::new(),
+                })
+                .with_font(theme::UI_DETAIL_FONT)
+                .with_text_color(theme::SECONDARY_TEXT_COLOR),
+            )
+            .padding((4.0, 0.0, 4.0, 4.0)),
+        SizedBox::empty(),
+    );
+
+    let picker_and_editor = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(coord_picker)
+                .with_child(coord_editor)
+                .with_child(bbox_info)
+                .padding(4.0),
+        )
+        .with_child(overshoot_info);
 
     // if we have any points selected, show the numerical adjust widget, else an empty widget
     Either::new(|d, _| d.count != 0, picker_and_editor, SizedBox::empty())
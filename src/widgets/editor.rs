@@ -1,30 +1,95 @@
 //! the main editor widget.
 
 use std::sync::Arc;
+use std::time::Instant;
 
+use druid::kurbo::Affine;
 use druid::widget::prelude::*;
-use druid::{Application, Clipboard, ClipboardFormat, Color, Command, ContextMenu, Data, KbKey};
+use druid::{
+    Application, Clipboard, ClipboardFormat, Color, Command, ContextMenu, Cursor, Data, KbKey,
+    KeyEvent, MouseButton,
+};
+use norad::Glyph;
 
 use crate::consts::{self, CANVAS_SIZE};
 use crate::data::EditorState;
+use crate::design_space::DPoint;
 use crate::draw;
-use crate::edit_session::EditSession;
+use crate::edit_session::{EditSession, MasterChange, PasteMode, PointKind};
+use crate::export;
+use crate::glyph_diff::GlyphDiff;
+use crate::metrics_keys;
 use crate::mouse::{Mouse, TaggedEvent};
-use crate::tools::{EditType, Select, Tool};
+use crate::path::Path;
+use crate::settings::{self, ButtonAction};
+use crate::theme;
+use crate::tools::{EditType, Preview, Select, Tool};
 use crate::undo::UndoState;
+use crate::widgets::{
+    anchor_prompt, component_prompt, glyph_lib, guide_prompt, png_export_prompt, ModalHost,
+};
 
 /// The root widget of the glyph editor window.
 pub struct Editor {
     mouse: Mouse,
     tool: Box<dyn Tool>,
+    /// Handles drags with a button set to pan, whatever the current tool.
+    panner: Preview,
+    /// `true` while a drag with a button set to pan, or one that began with
+    /// the space bar held, is in progress. A pan carries on until the mouse
+    /// is released, even if the space bar is released first.
+    panning: bool,
+    /// `true` while the space bar is held; a drag that starts then pans the
+    /// canvas, whatever the current tool.
+    space_down: bool,
     // in the case of the temporary preview (spacebar) this is the tool
     // that will be restored when spacebar is released.
     //prev_tool: Option<Box<dyn Tool>>,
-    undo: UndoState<Arc<EditSession>>,
+    undo: UndoState<UndoEntry>,
     last_edit: EditType,
+    /// When `last_edit` was made.
+    last_edit_time: Instant,
     /// If true, this session should be drawn with all glyphs filled and
     /// with no non-glyph items visible.
     draw_filled_outlines: bool,
+    /// If true, regions where the nonzero and even-odd fills differ are
+    /// highlighted.
+    highlight_overlaps: bool,
+    /// If true, the glyph as last saved is drawn behind it, with the points
+    /// that have changed since marked.
+    show_saved_diff: bool,
+    saved_diff: Option<SavedDiff>,
+    /// If false, off-curve points and their handles are not drawn, and tools
+    /// can't pick them; the session's `hide_handles` is kept in sync.
+    show_handles: bool,
+    /// If true, moved points snap to the grid set by `settings::GRID_SPACING`.
+    snap_to_grid: bool,
+}
+
+/// A state on the undo stack.
+#[derive(Clone)]
+struct UndoEntry {
+    session: Arc<EditSession>,
+    /// The changes to other masters made by the edit that led to this state,
+    /// which are reverted when it is undone.
+    masters: Option<Arc<MasterChange>>,
+}
+
+impl UndoEntry {
+    fn new(session: Arc<EditSession>) -> Self {
+        UndoEntry {
+            session,
+            masters: None,
+        }
+    }
+}
+
+/// The diff drawn when `show_saved_diff` is set, and what it was computed
+/// from, so that it is only recomputed when they change.
+struct SavedDiff {
+    paths: Arc<Vec<Path>>,
+    saved: Option<Arc<Glyph>>,
+    diff: GlyphDiff,
 }
 
 impl Editor {
@@ -32,10 +97,19 @@ impl Editor {
         Editor {
             mouse: Mouse::default(),
             tool: Box::new(Select::default()),
+            panner: Preview::default(),
+            panning: false,
+            space_down: false,
             //prev_tool: None,
-            undo: UndoState::new(session),
+            undo: UndoState::new(UndoEntry::new(session)),
             last_edit: EditType::Normal,
+            last_edit_time: Instant::now(),
             draw_filled_outlines: false,
+            highlight_overlaps: false,
+            show_saved_diff: false,
+            saved_diff: None,
+            show_handles: true,
+            snap_to_grid: false,
         }
     }
 
@@ -46,7 +120,30 @@ impl Editor {
         data: &mut EditorState,
         env: &Env,
     ) -> Option<EditType> {
-        if !event.inner().button.is_right() {
+        if let TaggedEvent::Down(m) = &event {
+            if self.mouse.is_up() {
+                let action = settings::button_action(env, m.button);
+                self.panning = self.space_down || action == ButtonAction::Pan;
+            }
+        }
+        if self.panning || (self.space_down && self.mouse.is_up()) {
+            ctx.set_cursor(&Cursor::OpenHand);
+        }
+        if self.panning {
+            match &event {
+                TaggedEvent::Down(_) => ctx.set_active(true),
+                TaggedEvent::Up(m) if m.buttons.is_empty() => ctx.set_active(false),
+                _ => (),
+            };
+            let is_up = matches!(&event, TaggedEvent::Up(_));
+            self.panner.pan_event(event, &mut self.mouse, ctx);
+            self.panning = !(is_up && self.mouse.is_up());
+            return None;
+        }
+
+        let shows_menu = event.inner().button.is_right()
+            && settings::button_action(env, MouseButton::Right) == ButtonAction::Default;
+        if !shows_menu {
             // set active, to ensure we receive events if the mouse leaves
             // the window:
             match &event {
@@ -67,26 +164,86 @@ impl Editor {
     }
 
     fn update_undo(&mut self, edit: Option<EditType>, data: &Arc<EditSession>) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_edit_time);
         match edit {
-            Some(edit) if self.last_edit.needs_new_undo_group(edit) => {
-                self.undo.add_undo_group(data.clone())
+            Some(edit) if self.last_edit.needs_new_undo_group(edit, elapsed) => {
+                self.undo.add_undo_group(UndoEntry::new(data.clone()))
             }
-            Some(_) => self.undo.update_current_undo(|state| *state = data.clone()),
+            Some(_) => self.undo.update_current_undo(|e| e.session = data.clone()),
             // I'm not sure what to do here? I wanted to check if selections had
             // changed, and then update the current undo if necessary?
             // but that requires us to pass in the previous data. We can do that!
             // I'm just not sure, right now, that it makes sense
             None => (),
         }
-        self.last_edit = edit.unwrap_or(self.last_edit);
+        if let Some(edit) = edit {
+            self.last_edit = edit;
+            self.last_edit_time = now;
+        }
+    }
+
+    /// Undo the last edit, returning the previous session; any changes the
+    /// edit made to other masters are reverted, too.
+    fn do_undo(&mut self, ctx: &mut EventCtx) -> Option<Arc<EditSession>> {
+        let masters = self.undo.current().masters.clone();
+        let prev = self.undo.undo()?.session.clone();
+        if let Some(change) = masters {
+            ctx.submit_command(consts::cmd::SET_MASTER_GLYPHS.with(change.before.clone()));
+        }
+        Some(prev)
+    }
+
+    fn do_redo(&mut self, ctx: &mut EventCtx) -> Option<Arc<EditSession>> {
+        let next = self.undo.redo()?;
+        if let Some(change) = next.masters.as_ref() {
+            ctx.submit_command(consts::cmd::SET_MASTER_GLYPHS.with(change.after.clone()));
+        }
+        Some(next.session.clone())
     }
 
-    fn do_undo(&mut self) -> Option<&Arc<EditSession>> {
-        self.undo.undo()
+    /// Repeat the last edit in the other masters, as an edit of its own.
+    fn apply_to_all_masters(&mut self, ctx: &mut EventCtx, data: &EditorState) {
+        let prev = self.undo.previous();
+        let edit = match prev.and_then(|prev| data.session.edit_since(&prev.session)) {
+            Some(edit) => edit,
+            None => {
+                log::warn!("only edits that move points can be repeated");
+                return;
+            }
+        };
+        let change = data.session.apply_to_all_masters(&edit, &data.font);
+        let (name, count) = (&data.session.name, change.after.len());
+        log::info!("applied edit to '{}' in {} masters", name, count);
+        if change.after.is_empty() {
+            return;
+        }
+        ctx.submit_command(consts::cmd::SET_MASTER_GLYPHS.with(change.after.clone()));
+        // the session is unchanged, but this gets its own undo group, so that
+        // the masters can be changed back
+        let entry = UndoEntry {
+            session: data.session.clone(),
+            masters: Some(Arc::new(change)),
+        };
+        self.undo.add_undo_group(entry);
+        self.last_edit = EditType::Normal;
     }
 
-    fn do_redo(&mut self) -> Option<&Arc<EditSession>> {
-        self.undo.redo()
+    /// The diff between the session and the saved glyph.
+    fn saved_diff(&mut self, data: &EditorState) -> &GlyphDiff {
+        let paths = &data.session.paths;
+        let saved = data.font.font.saved_glyph(&data.session.name).cloned();
+        let fresh = self.saved_diff.as_ref().map_or(false, |cached| {
+            cached.paths.same(paths) && cached.saved.same(&saved)
+        });
+        if !fresh {
+            self.saved_diff = Some(SavedDiff {
+                paths: paths.clone(),
+                saved,
+                diff: data.session.diff_against_saved(&data.font),
+            });
+        }
+        &self.saved_diff.as_ref().unwrap().diff
     }
 
     fn do_copy(&self, data: &EditSession) {
@@ -115,7 +272,22 @@ impl Editor {
         }
     }
 
-    fn do_paste(&self, session: &mut EditSession, clipboard: &Clipboard) -> Option<EditType> {
+    /// The paste mode to use; pasting at the cursor uses the last position
+    /// of the mouse in the editor.
+    fn paste_mode(&self, data: &EditorState, at_cursor: bool) -> PasteMode {
+        if at_cursor {
+            PasteMode::Centered(data.session.viewport.from_screen(self.mouse.pos()))
+        } else {
+            PasteMode::InPlace
+        }
+    }
+
+    fn do_paste(
+        &self,
+        session: &mut EditSession,
+        clipboard: &Clipboard,
+        mode: PasteMode,
+    ) -> Option<EditType> {
         let paste_types = [
             crate::consts::GLYPHS_APP_PASTEBOARD_TYPE,
             ClipboardFormat::PDF,
@@ -137,7 +309,7 @@ impl Editor {
                 _ => None,
             };
             if let Some(paths) = paths {
-                session.paste_paths(paths);
+                session.paste(paths, mode);
                 return Some(EditType::Normal);
             }
         }
@@ -148,11 +320,36 @@ impl Editor {
     /// handle a `Command`. Returns a bool indicating whether the command was
     /// handled at all, and an optional `EditType` if this command did work
     /// that should go on the undo stack.
-    fn handle_cmd(&mut self, cmd: &Command, data: &mut EditorState) -> (bool, Option<EditType>) {
+    fn handle_cmd(
+        &mut self,
+        ctx: &mut EventCtx,
+        cmd: &Command,
+        data: &mut EditorState,
+    ) -> (bool, Option<EditType>) {
         match cmd {
             c if c.is(consts::cmd::SELECT_ALL) => data.session_mut().select_all(),
             c if c.is(consts::cmd::DESELECT_ALL) => data.session_mut().selection.clear(),
-            c if c.is(consts::cmd::DELETE) => data.session_mut().delete_selection(),
+            c if c.is(consts::cmd::SELECT_ON_CURVE) => {
+                data.session_mut().select_by_kind(PointKind::OnCurve, false);
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::SELECT_OFF_CURVE) => {
+                data.session_mut()
+                    .select_by_kind(PointKind::OffCurve, false);
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::SELECT_NEXT_CONTOUR) => data.session_mut().select_next_contour(),
+            c if c.is(consts::cmd::SELECT_PREV_CONTOUR) => data.session_mut().select_prev_contour(),
+            c if c.is(consts::cmd::DELETE) => {
+                data.session_mut().delete_selection();
+                return (true, Some(EditType::Delete));
+            }
+            c if c.is(consts::cmd::PASTE_IN_PLACE) || c.is(consts::cmd::PASTE_AT_CURSOR) => {
+                let mode = self.paste_mode(data, c.is(consts::cmd::PASTE_AT_CURSOR));
+                let clipboard = Application::global().clipboard();
+                let edit = self.do_paste(data.session_mut(), &clipboard, mode);
+                return (true, edit);
+            }
             c if c.is(consts::cmd::TOGGLE_PREVIEW_TOOL) => {
                 let is_mouse_down: &bool = cmd.get_unchecked(consts::cmd::TOGGLE_PREVIEW_TOOL);
                 // we don't toggle preview if we're actually *in* preview
@@ -161,11 +358,33 @@ impl Editor {
                     return (true, None);
                 }
             }
+            c if c.is(consts::cmd::TOGGLE_OVERLAP_HIGHLIGHT) => {
+                self.highlight_overlaps = !self.highlight_overlaps;
+                return (true, None);
+            }
+            c if c.is(consts::cmd::TOGGLE_HANDLES) => {
+                self.show_handles = !self.show_handles;
+                data.session_mut().hide_handles = !self.show_handles;
+                return (true, None);
+            }
+            c if c.is(consts::cmd::TOGGLE_GRID_SNAP) => {
+                self.snap_to_grid = !self.snap_to_grid;
+                return (true, None);
+            }
+            c if c.is(consts::cmd::TOGGLE_SAVED_DIFF) => {
+                self.show_saved_diff = !self.show_saved_diff;
+                return (true, None);
+            }
             c if c.is(consts::cmd::ADD_GUIDE) => {
                 let point = cmd.get_unchecked(consts::cmd::ADD_GUIDE);
                 data.session_mut().add_guide(*point);
                 return (true, Some(EditType::Normal));
             }
+            c if c.is(consts::cmd::ADD_GUIDE_AT) => {
+                let spec = cmd.get_unchecked(consts::cmd::ADD_GUIDE_AT);
+                data.session_mut().add_guide_at(*spec);
+                return (true, Some(EditType::Normal));
+            }
             c if c.is(consts::cmd::TOGGLE_GUIDE) => {
                 let consts::cmd::ToggleGuideCmdArgs { id, pos } =
                     cmd.get_unchecked(consts::cmd::TOGGLE_GUIDE);
@@ -174,24 +393,34 @@ impl Editor {
             }
             c if c.is(druid::commands::COPY) => self.do_copy(&data.session),
             c if c.is(druid::commands::UNDO) => {
-                if let Some(prev) = self.do_undo() {
+                if let Some(prev) = self.do_undo(ctx) {
                     //HACK: because zoom & offset is part of data, and we don't
                     //want to jump around during undo/redo, we always manually
                     //reuse the current viewport when handling these actions.
                     let saved_viewport = data.session.viewport;
-                    data.session = prev.clone();
+                    data.session = prev;
                     data.session_mut().viewport = saved_viewport;
                 }
             }
             c if c.is(druid::commands::REDO) => {
-                if let Some(next) = self.do_redo() {
+                if let Some(next) = self.do_redo(ctx) {
                     let saved_viewport = data.session.viewport;
-                    data.session = next.clone();
+                    data.session = next;
                     data.session_mut().viewport = saved_viewport;
                 }
             }
             c if c.is(consts::cmd::ALIGN_SELECTION) => {
-                data.session_mut().align_selection();
+                data.session_mut().align_selection_centers();
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::ALIGN_SELECTION_TO) => {
+                let kind = c.get_unchecked(consts::cmd::ALIGN_SELECTION_TO);
+                data.session_mut().align_selection(*kind);
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::DISTRIBUTE_SELECTION) => {
+                let axis = c.get_unchecked(consts::cmd::DISTRIBUTE_SELECTION);
+                data.session_mut().distribute_selection(*axis);
                 return (true, Some(EditType::Normal));
             }
             c if c.is(consts::cmd::NUDGE_SELECTION) => {
@@ -199,6 +428,12 @@ impl Editor {
                 data.session_mut().nudge_selection(*nudge);
                 return (true, Some(EditType::Normal));
             }
+            c if c.is(consts::cmd::SET_SELECTION_OVERSHOOT) => {
+                let args = c.get_unchecked(consts::cmd::SET_SELECTION_OVERSHOOT);
+                data.session_mut()
+                    .set_selection_overshoot(args.metric, args.value);
+                return (true, Some(EditType::Normal));
+            }
             c if c.is(consts::cmd::ADJUST_SIDEBEARING) => {
                 let adjust = c.get_unchecked(consts::cmd::ADJUST_SIDEBEARING);
                 data.session_mut()
@@ -212,7 +447,86 @@ impl Editor {
                 data.session_mut().scale_selection(*scale, *origin);
             }
             c if c.is(consts::cmd::REVERSE_CONTOURS) => {
-                data.session_mut().reverse_contours();
+                data.session_mut().reverse_selected_contours();
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::CORRECT_PATH_DIRECTION) => {
+                if data.session_mut().correct_path_direction() {
+                    return (true, Some(EditType::Normal));
+                }
+            }
+            c if c.is(consts::cmd::TOGGLE_TANGENT) => {
+                data.session_mut().toggle_selected_tangent();
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::INSERT_COMPONENT) => {
+                let base = c.get_unchecked(consts::cmd::INSERT_COMPONENT);
+                data.session_mut()
+                    .add_component(base.clone(), Affine::IDENTITY);
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::SET_ANCHOR_NAME) => {
+                let args = c.get_unchecked(consts::cmd::SET_ANCHOR_NAME);
+                let name = args.name.clone();
+                match args.target {
+                    consts::cmd::AnchorTarget::New(pos) => {
+                        let point = DPoint::from_screen(pos, data.session.viewport);
+                        data.session_mut().add_anchor(name, point);
+                    }
+                    consts::cmd::AnchorTarget::Existing(id) => {
+                        if !data.session_mut().rename_anchor(id, name) {
+                            return (true, None);
+                        }
+                    }
+                }
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::SET_LIB_VALUE) => {
+                let args = c.get_unchecked(consts::cmd::SET_LIB_VALUE);
+                data.session_mut()
+                    .set_lib_value(args.key.clone(), args.value.clone());
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::SMOOTH_SELECTION) => {
+                data.session_mut().smooth_selection();
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::REMOVE_STRAY_OFFCURVES) => {
+                let removed = data.session_mut().remove_stray_offcurves();
+                log::info!("removed {} stray off-curve points", removed);
+                if removed == 0 {
+                    return (true, None);
+                }
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::CLOSE_ALL_CONTOURS) => {
+                let closed = data.session_mut().close_all_open_contours();
+                log::info!("closed {} open contours", closed);
+                if closed == 0 {
+                    return (true, None);
+                }
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::MAKE_G2_CONTINUOUS) => {
+                data.session_mut().make_g2_continuous_at_selection();
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::RESYNC_METRICS_KEYS) => {
+                let name = data.session.name.clone();
+                let mismatches = metrics_keys::glyph_mismatches(&data.font, &name);
+                if mismatches.is_empty() {
+                    return (true, None);
+                }
+                for mismatch in mismatches {
+                    let is_left = mismatch.side == metrics_keys::Side::Left;
+                    data.session_mut()
+                        .adjust_sidebearing(mismatch.delta(), is_left);
+                }
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::OFFSET_OUTLINE) => {
+                let distance = c.get_unchecked(consts::cmd::OFFSET_OUTLINE);
+                data.session_mut().offset_outline(*distance);
                 return (true, Some(EditType::Normal));
             }
             // all unhandled commands:
@@ -243,9 +557,19 @@ impl Widget<EditorState> for Editor {
             &data.metrics,
             &data.session,
             &data.font,
-            self.draw_filled_outlines,
+            draw::DrawOptions {
+                filled: self.draw_filled_outlines,
+                highlight_overlaps: self.highlight_overlaps,
+                hide_handles: data.session.hide_handles,
+            },
         );
 
+        if self.show_saved_diff && !self.draw_filled_outlines {
+            let ghost_color = env.get(theme::PLACEHOLDER_GLYPH_COLOR);
+            let diff = self.saved_diff(data);
+            draw::draw_glyph_diff(ctx, diff, data.session.viewport, &ghost_color);
+        }
+
         self.tool.paint(ctx, &data.session, env);
     }
 
@@ -260,10 +584,52 @@ impl Widget<EditorState> for Editor {
     }
 
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut EditorState, env: &Env) {
+        // components are hit-tested with their base glyph's outline, which
+        // the session can't look up itself. This is done before we note the
+        // components below, since it isn't an edit.
+        let outlines: Vec<_> = data
+            .session
+            .components
+            .iter()
+            .map(|c| data.font.get_bezier(&c.base))
+            .collect();
+        let outlines_changed = outlines
+            .iter()
+            .zip(data.session.components.iter())
+            .any(|(outline, c)| !outline.same(&c.outline));
+        if outlines_changed {
+            let components = data.session_mut().components_mut();
+            for (component, outline) in components.iter_mut().zip(outlines) {
+                component.outline = outline;
+            }
+        }
+
         // we request_paint if selection changes after this event;
         let pre_selection = data.session.selection.clone();
         let pre_paths = data.session.paths.clone();
         let pre_components = data.session.components.clone();
+        let pre_guides = data.session.guides.clone();
+
+        // tools only see the session, so we keep its copies of the metrics and
+        // settings current
+        if !data.session.metrics.same(&data.metrics) {
+            data.session_mut().metrics = data.metrics.clone();
+        }
+        let snap = settings::snap_config(env);
+        if data.session.snap != snap {
+            data.session_mut().snap = snap;
+        }
+        let proportional_handles = env.get(settings::PROPORTIONAL_HANDLES);
+        if data.session.proportional_handles != proportional_handles {
+            data.session_mut().proportional_handles = proportional_handles;
+        }
+        let grid = Some(env.get(settings::GRID_SPACING)).filter(|_| self.snap_to_grid);
+        if data.session.grid != grid {
+            data.session_mut().grid = grid;
+        }
+        if data.session.hide_handles == self.show_handles {
+            data.session_mut().hide_handles = !self.show_handles;
+        }
 
         let edit = match event {
             Event::WindowConnected => {
@@ -275,12 +641,63 @@ impl Widget<EditorState> for Editor {
                     ctx.request_focus();
                     ctx.set_handled();
                     None
+                } else if cmd.is(consts::cmd::SHOW_GLYPH_LIB) {
+                    let cmd = ModalHost::<EditorState>::make_modal_command(glyph_lib);
+                    ctx.submit_command(cmd);
+                    ctx.set_handled();
+                    None
+                } else if cmd.is(consts::cmd::SHOW_EXPORT_PNG_PROMPT) {
+                    let cmd = ModalHost::<EditorState>::make_modal_command(png_export_prompt);
+                    ctx.submit_command(cmd);
+                    ctx.set_handled();
+                    None
+                } else if let Some(options) = cmd.get(consts::cmd::EXPORT_PNG) {
+                    let path = export::png_path(&data.font, &data.session.name);
+                    let session = &data.session;
+                    match export::export_png(session, &data.font, &data.metrics, options, &path) {
+                        Ok(()) => log::info!("exported '{}' to {:?}", data.session.name, path),
+                        Err(e) => log::error!("failed to export {:?}: '{}'", path, e),
+                    }
+                    ctx.set_handled();
+                    None
+                } else if cmd.is(consts::cmd::APPLY_TO_ALL_MASTERS) {
+                    self.apply_to_all_masters(ctx, data);
+                    ctx.set_handled();
+                    None
+                } else if cmd.is(consts::cmd::SHOW_USE_AS_COMPONENT_PROMPT) {
+                    let cmd = ModalHost::<EditorState>::make_modal_command(component_prompt);
+                    ctx.submit_command(cmd);
+                    ctx.set_handled();
+                    None
+                } else if let Some(target) = cmd.get(consts::cmd::SHOW_ANCHOR_PROMPT) {
+                    let target = *target;
+                    let name = match target {
+                        consts::cmd::AnchorTarget::Existing(id) => data
+                            .session
+                            .anchors
+                            .iter()
+                            .find(|a| a.id == id)
+                            .map(|a| a.name.clone())
+                            .unwrap_or_default(),
+                        consts::cmd::AnchorTarget::New(_) => String::new(),
+                    };
+                    let cmd = ModalHost::<EditorState>::make_modal_command(move || {
+                        anchor_prompt(target, name)
+                    });
+                    ctx.submit_command(cmd);
+                    ctx.set_handled();
+                    None
+                } else if cmd.is(consts::cmd::SHOW_GUIDE_PROMPT) {
+                    let cmd = ModalHost::<EditorState>::make_modal_command(guide_prompt);
+                    ctx.submit_command(cmd);
+                    ctx.set_handled();
+                    None
                 } else if let Some(tool) = cmd.get(consts::cmd::SET_TOOL) {
                     let tool = crate::tools::tool_for_id(tool).unwrap();
                     self.set_tool(tool);
                     None
                 } else {
-                    let (handled, edit) = self.handle_cmd(cmd, data);
+                    let (handled, edit) = self.handle_cmd(ctx, cmd, data);
                     if handled {
                         ctx.set_handled();
                         ctx.request_paint();
@@ -292,12 +709,29 @@ impl Widget<EditorState> for Editor {
                 data.session_mut().selection.clear();
                 None
             }
+            Event::KeyDown(k) if is_space(k) => {
+                self.space_down = true;
+                if self.mouse.is_up() {
+                    ctx.set_cursor(&Cursor::OpenHand);
+                }
+                None
+            }
+            Event::KeyUp(k) if is_space(k) => {
+                self.space_down = false;
+                if !self.panning {
+                    ctx.set_cursor(&Cursor::Arrow);
+                }
+                None
+            }
             Event::KeyDown(k) => self.tool.key_down(k, ctx, data.session_mut(), env),
             Event::KeyUp(k) => self.tool.key_up(k, ctx, data.session_mut(), env),
             Event::MouseUp(m) => self.send_mouse(ctx, TaggedEvent::Up(m.clone()), data, env),
             Event::MouseMove(m) => self.send_mouse(ctx, TaggedEvent::Moved(m.clone()), data, env),
             Event::MouseDown(m) => self.send_mouse(ctx, TaggedEvent::Down(m.clone()), data, env),
-            Event::Paste(clipboard) => self.do_paste(data.session_mut(), clipboard),
+            Event::Paste(clipboard) => {
+                let mode = self.paste_mode(data, env.get(settings::PASTE_AT_CURSOR));
+                self.do_paste(data.session_mut(), clipboard, mode)
+            }
             _ => None,
         };
 
@@ -306,16 +740,32 @@ impl Widget<EditorState> for Editor {
             ctx.request_paint();
         }
 
-        if !pre_paths.same(&data.session.paths) || !pre_components.same(&data.session.components) {
+        if !pre_paths.same(&data.session.paths)
+            || !pre_components.same(&data.session.components)
+            || !pre_guides.same(&data.session.guides)
+        {
             data.session_mut().rebuild_glyph();
         }
     }
 
-    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &EditorState, _: &Env) {}
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, event: &LifeCycle, _: &EditorState, _: &Env) {
+        // we won't see the space bar's key-up if we lose focus while it is held
+        if let LifeCycle::FocusChanged(false) = event {
+            self.space_down = false;
+        }
+    }
 
     fn update(&mut self, ctx: &mut UpdateCtx, old: &EditorState, new: &EditorState, _env: &Env) {
+        // undoing past a switch of masters would mix up the masters' glyphs
+        if old.font.active_master() != new.font.active_master() {
+            self.undo = UndoState::new(UndoEntry::new(new.session.clone()));
+        }
         if !old.same(new) {
             ctx.request_paint();
         }
     }
 }
+
+fn is_space(event: &KeyEvent) -> bool {
+    matches!(&event.key, KbKey::Character(s) if s.as_str() == " ")
+}
@@ -68,12 +68,23 @@ pub fn font_info() -> impl Widget<Workspace> {
                         .lens(SimpleFontInfo::metrics.then(FontMetrics::descender)),
                 ),
         )
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Label::new("Italic angle:").with_text_color(theme::SECONDARY_TEXT_COLOR),
+                )
+                .with_default_spacer()
+                .with_child(
+                    option_f64_editlabel()
+                        .lens(SimpleFontInfo::metrics.then(FontMetrics::italic_angle)),
+                ),
+        )
         .with_flex_spacer(1.0)
         .with_child(
             Button::new("Done").on_click(|ctx, _, _| ctx.submit_command(ModalHost::DISMISS_MODAL)),
         )
         .cross_axis_alignment(CrossAxisAlignment::End)
-        .fix_height(300.)
+        .fix_height(320.)
         .padding(16.0)
         .background(Color::WHITE)
         .lens(Workspace::info)
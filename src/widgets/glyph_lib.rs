@@ -0,0 +1,202 @@
+//! A panel for viewing and editing the current glyph's lib.
+//!
+//! This is intended to be shown as a modal panel in the editor window.
+//! Strings and numbers can be edited in place; other values are only shown.
+
+use std::sync::Arc;
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, List, Scroll, ViewSwitcher};
+use druid::{Color, Lens, WidgetExt, WidgetPod};
+use plist::Value;
+
+use crate::consts;
+use crate::data::EditorState;
+use crate::edit_session::EditSession;
+use crate::theme;
+use crate::widgets::{EditableLabel, ModalHost};
+
+type Entries = Arc<Vec<LibEntry>>;
+
+/// The kinds of lib values we know how to edit.
+#[derive(Debug, Clone, Copy, Data, PartialEq)]
+enum Kind {
+    String,
+    Integer,
+    Real,
+    /// Anything else, which is shown but can't be edited.
+    Other,
+}
+
+/// A single lib entry, as displayed.
+#[derive(Debug, Clone, Data, Lens)]
+struct LibEntry {
+    key: Arc<str>,
+    kind: Kind,
+    value: String,
+}
+
+/// A widget that owns the displayed entries, which are rebuilt whenever the
+/// glyph changes.
+struct GlyphLib {
+    entries: Entries,
+    inner: WidgetPod<Entries, Box<dyn Widget<Entries>>>,
+}
+
+pub fn glyph_lib() -> impl Widget<EditorState> {
+    GlyphLib {
+        entries: Arc::new(Vec::new()),
+        inner: WidgetPod::new(build_widget().boxed()),
+    }
+}
+
+fn build_widget() -> impl Widget<Entries> {
+    Flex::column()
+        .with_child(Label::new("Glyph lib:"))
+        .with_default_spacer()
+        .with_flex_child(Scroll::new(List::new(entry_row)).vertical(), 1.0)
+        .with_default_spacer()
+        .with_child(
+            Button::new("Done").on_click(|ctx, _, _| ctx.submit_command(ModalHost::DISMISS_MODAL)),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::End)
+        .fix_size(480., 320.)
+        .padding(16.0)
+        .background(Color::WHITE)
+}
+
+fn entry_row() -> impl Widget<LibEntry> {
+    let value = ViewSwitcher::new(
+        |entry: &LibEntry, _| entry.kind,
+        |kind, _, _| match kind {
+            Kind::Other => Label::dynamic(|value: &String, _| value.clone())
+                .with_text_color(theme::SECONDARY_TEXT_COLOR)
+                .lens(LibEntry::value)
+                .boxed(),
+            kind => {
+                let kind = *kind;
+                EditableLabel::new(
+                    |value: &String, _: &_| value.clone(),
+                    move |s| parse_value(kind, s).map(|_| s.to_string()),
+                )
+                .lens(LibEntry::value)
+                .boxed()
+            }
+        },
+    );
+
+    Flex::row()
+        .with_child(
+            Label::dynamic(|key: &Arc<str>, _| key.to_string())
+                .with_font(theme::UI_DETAIL_FONT)
+                .lens(LibEntry::key)
+                .fix_width(240.),
+        )
+        .with_flex_child(value, 1.0)
+        .padding((0.0, 2.0))
+}
+
+fn entries_for(session: &EditSession) -> Entries {
+    let entries = session
+        .lib()
+        .map(|lib| {
+            lib.iter()
+                .map(|(key, value)| {
+                    let (kind, value) = describe_value(value);
+                    LibEntry {
+                        key: key.as_str().into(),
+                        kind,
+                        value,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Arc::new(entries)
+}
+
+fn describe_value(value: &Value) -> (Kind, String) {
+    match value {
+        Value::String(s) => (Kind::String, s.clone()),
+        Value::Real(r) => (Kind::Real, r.to_string()),
+        Value::Integer(_) => match value.as_signed_integer() {
+            Some(int) => (Kind::Integer, int.to_string()),
+            None => (Kind::Other, format!("{:?}", value)),
+        },
+        Value::Boolean(b) => (Kind::Other, b.to_string()),
+        Value::Array(array) => (Kind::Other, format!("array ({} items)", array.len())),
+        Value::Dictionary(dict) => (Kind::Other, format!("dictionary ({} items)", dict.len())),
+        Value::Data(data) => (Kind::Other, format!("data ({} bytes)", data.len())),
+        other => (Kind::Other, format!("{:?}", other)),
+    }
+}
+
+fn parse_value(kind: Kind, text: &str) -> Option<Value> {
+    match kind {
+        Kind::String => Some(Value::String(text.to_string())),
+        Kind::Integer => text.trim().parse::<i64>().ok().map(Value::from),
+        Kind::Real => text.trim().parse::<f64>().ok().map(Value::Real),
+        Kind::Other => None,
+    }
+}
+
+impl Widget<EditorState> for GlyphLib {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _: &mut EditorState, env: &Env) {
+        let pre_entries = self.entries.clone();
+        self.inner.event(ctx, event, &mut self.entries, env);
+        if pre_entries.same(&self.entries) {
+            return;
+        }
+
+        // rows are never added or removed here, only edited.
+        for (old, new) in pre_entries.iter().zip(self.entries.iter()) {
+            if old.value != new.value {
+                if let Some(value) = parse_value(new.kind, &new.value) {
+                    let args = consts::cmd::SetLibValueArgs {
+                        key: new.key.to_string(),
+                        value,
+                    };
+                    ctx.submit_command(consts::cmd::SET_LIB_VALUE.with(args));
+                }
+            }
+        }
+        ctx.request_update();
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &EditorState,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.entries = entries_for(&data.session);
+        }
+        self.inner.lifecycle(ctx, event, &self.entries, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old: &EditorState, data: &EditorState, env: &Env) {
+        if !old.session.glyph.same(&data.session.glyph) {
+            self.entries = entries_for(&data.session);
+        }
+        self.inner.update(ctx, &self.entries, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _: &EditorState,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, &self.entries, env);
+        self.inner
+            .set_layout_rect(ctx, &self.entries, env, size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _: &EditorState, env: &Env) {
+        self.inner.paint(ctx, &self.entries, env);
+    }
+}
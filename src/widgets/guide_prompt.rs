@@ -0,0 +1,21 @@
+//! A prompt for placing a guide at an exact position.
+//!
+//! This is intended to be shown as a modal panel in the editor window.
+
+use druid::Widget;
+
+use crate::consts;
+use crate::data::EditorState;
+use crate::guides::GuideSpec;
+use crate::widgets::prompt::text_prompt;
+
+pub fn guide_prompt() -> impl Widget<EditorState> {
+    text_prompt(
+        "Add guide at:",
+        "x 100, y 500, or x, y, angle",
+        "Add",
+        String::new(),
+        |text, _| text.parse::<GuideSpec>(),
+        |ctx, spec| ctx.submit_command(consts::cmd::ADD_GUIDE_AT.with(spec)),
+    )
+}
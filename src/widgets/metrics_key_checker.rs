@@ -0,0 +1,162 @@
+//! A list of glyphs whose sidebearings don't match their metrics keys.
+//!
+//! This is intended to be shown as a modal panel.
+
+use std::sync::Arc;
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Either, Flex, Label, List, Scroll};
+use druid::{Color, WidgetExt, WidgetPod};
+use norad::GlyphName;
+
+use crate::consts;
+use crate::data::Workspace;
+use crate::metrics_keys::{self, Mismatch, Side};
+use crate::theme;
+use crate::widgets::ModalHost;
+
+type Mismatches = Arc<Vec<Mismatch>>;
+
+/// A widget that owns the list of mismatches.
+///
+/// The list is computed when the panel is opened, so that fixed glyphs stay
+/// in the list (marked as fixed) instead of disappearing from under the
+/// pointer.
+struct MetricsKeyChecker {
+    mismatches: Mismatches,
+    inner: WidgetPod<Mismatches, Box<dyn Widget<Mismatches>>>,
+}
+
+pub fn metrics_key_checker() -> impl Widget<Workspace> {
+    MetricsKeyChecker {
+        mismatches: Arc::new(Vec::new()),
+        inner: WidgetPod::new(build_widget().boxed()),
+    }
+}
+
+fn build_widget() -> impl Widget<Mismatches> {
+    let empty =
+        Label::new("All metrics keys are in sync.").with_text_color(theme::SECONDARY_TEXT_COLOR);
+    let list = Scroll::new(List::new(mismatch_row))
+        .vertical()
+        .fix_height(220.);
+
+    Flex::column()
+        .with_child(Label::new(
+            "Sidebearings that don't match their metrics keys:",
+        ))
+        .with_default_spacer()
+        .with_child(Either::new(|d: &Mismatches, _| d.is_empty(), empty, list))
+        .with_flex_spacer(1.0)
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Button::new("Fix All").on_click(|ctx, data: &mut Mismatches, _| {
+                        fix_all(ctx, data);
+                    }),
+                )
+                .with_default_spacer()
+                .with_child(
+                    Button::new("Done")
+                        .on_click(|ctx, _, _| ctx.submit_command(ModalHost::DISMISS_MODAL)),
+                ),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::End)
+        .fix_height(320.)
+        .padding(16.0)
+        .background(Color::WHITE)
+}
+
+fn mismatch_row() -> impl Widget<Mismatch> {
+    let fixed = Label::new("fixed")
+        .with_text_color(theme::SECONDARY_TEXT_COLOR)
+        .with_font(theme::UI_DETAIL_FONT);
+    let fix_button = Button::new("Fix").on_click(|ctx, data: &mut Mismatch, _| {
+        fix_mismatch(ctx, data);
+    });
+
+    Flex::row()
+        .with_child(Label::dynamic(|d: &Mismatch, _| d.glyph.to_string()).fix_width(100.))
+        .with_child(
+            Label::dynamic(|d: &Mismatch, _| {
+                let side = match d.side {
+                    Side::Left => "left",
+                    Side::Right => "right",
+                };
+                format!("{}: {:.0} → {:.0}", side, d.actual, d.expected)
+            })
+            .with_text_color(theme::SECONDARY_TEXT_COLOR)
+            .fix_width(140.),
+        )
+        .with_child(Either::new(|d: &Mismatch, _| d.fixed, fixed, fix_button))
+        .padding((0.0, 2.0))
+}
+
+/// Ask the delegate to resync this glyph, if we haven't already.
+fn fix_mismatch(ctx: &mut EventCtx, mismatch: &mut Mismatch) {
+    if !mismatch.fixed {
+        mismatch.fixed = true;
+        ctx.submit_command(consts::cmd::FIX_METRICS_KEYS.with(mismatch.glyph.clone()));
+    }
+}
+
+/// Ask the delegate to resync every glyph we haven't already fixed, without
+/// opening an editor for each of them.
+fn fix_all(ctx: &mut EventCtx, mismatches: &mut Mismatches) {
+    let mut names: Vec<GlyphName> = Vec::new();
+    for mismatch in Arc::make_mut(mismatches).iter_mut() {
+        if !mismatch.fixed {
+            mismatch.fixed = true;
+            if !names.contains(&mismatch.glyph) {
+                names.push(mismatch.glyph.clone());
+            }
+        }
+    }
+    if !names.is_empty() {
+        ctx.submit_command(consts::cmd::FIX_ALL_METRICS_KEYS.with(names));
+    }
+}
+
+impl Widget<Workspace> for MetricsKeyChecker {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _: &mut Workspace, env: &Env) {
+        let pre_mismatches = self.mismatches.clone();
+        self.inner.event(ctx, event, &mut self.mismatches, env);
+        if !pre_mismatches.same(&self.mismatches) {
+            ctx.request_update();
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Workspace,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.mismatches = Arc::new(metrics_keys::find_mismatches(data));
+        }
+        self.inner.lifecycle(ctx, event, &self.mismatches, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &Workspace, _: &Workspace, env: &Env) {
+        self.inner.update(ctx, &self.mismatches, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _: &Workspace,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, &self.mismatches, env);
+        self.inner
+            .set_layout_rect(ctx, &self.mismatches, env, size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _: &Workspace, env: &Env) {
+        self.inner.paint(ctx, &self.mismatches, env);
+    }
+}
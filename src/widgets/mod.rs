@@ -1,29 +1,44 @@
 //! Druid `Widget`s.
 
+mod anchor_prompt;
+mod component_prompt;
 mod controller;
 mod coord_pane;
 mod editable_label;
 mod editor;
 mod fontinfo;
 mod glyph;
+mod glyph_lib;
 mod glyph_pane;
 mod grid;
+mod guide_prompt;
 mod maybe;
+mod metrics_key_checker;
 mod modal_host;
+mod pixel_preview;
+mod png_export;
+mod prompt;
 mod scroll_zoom;
 mod sidebar;
 mod toolbar;
 
+pub use anchor_prompt::anchor_prompt;
+pub use component_prompt::component_prompt;
 pub use controller::{EditorController, RootWindowController};
 pub use coord_pane::CoordPane;
 pub use editable_label::EditableLabel;
 pub use editor::Editor;
 pub use fontinfo::font_info;
 pub use glyph::GlyphPainter;
+pub use glyph_lib::glyph_lib;
 pub use glyph_pane::GlyphPane;
 pub use grid::GlyphGrid;
+pub use guide_prompt::guide_prompt;
 use maybe::Maybe;
+pub use metrics_key_checker::metrics_key_checker;
 pub use modal_host::ModalHost;
+use pixel_preview::pixel_preview;
+pub use png_export::png_export_prompt;
 pub use scroll_zoom::ScrollZoom;
 pub use sidebar::Sidebar;
 pub use toolbar::{FloatingPanel, Toolbar};
@@ -0,0 +1,143 @@
+//! A floating panel that shows the glyph rasterized at small pixel sizes.
+
+use druid::kurbo::{BezPath, Rect};
+use druid::widget::{prelude::*, CrossAxisAlignment, Flex, Label};
+use druid::{Color, WidgetExt};
+
+use crate::data::EditorState;
+use crate::raster::{self, Coverage};
+use crate::theme;
+
+/// The sizes we preview, in pixels per em.
+const PREVIEW_SIZES: &[f64] = &[12.0, 16.0, 24.0];
+/// The size of each preview pixel, on screen.
+const CELL_SIZE: f64 = 3.0;
+const GRID_COLOR: Color = Color::rgb8(0xE8, 0xE8, 0xE8);
+
+/// The glyph, rasterized at a single size.
+///
+/// Rasterizing is done when painting, so that nothing is done while the panel
+/// is hidden, and only if the outline or its bounds have changed.
+struct PixelGrid {
+    ppem: f64,
+    /// Set when the session has changed since we last painted.
+    stale: bool,
+    /// The outline and bounds that `coverage` was rasterized from.
+    source: Option<(BezPath, Rect)>,
+    coverage: Option<Coverage>,
+}
+
+pub fn pixel_preview() -> impl Widget<EditorState> {
+    let mut row = Flex::row().cross_axis_alignment(CrossAxisAlignment::End);
+    for (i, ppem) in PREVIEW_SIZES.iter().enumerate() {
+        if i > 0 {
+            row.add_default_spacer();
+        }
+        row.add_child(
+            Flex::column()
+                .with_child(PixelGrid {
+                    ppem: *ppem,
+                    stale: true,
+                    source: None,
+                    coverage: None,
+                })
+                .with_spacer(4.0)
+                .with_child(
+                    Label::new(format!("{} px", ppem))
+                        .with_font(theme::UI_DETAIL_FONT)
+                        .with_text_color(theme::SECONDARY_TEXT_COLOR),
+                ),
+        );
+    }
+    row.padding(8.0)
+}
+
+impl PixelGrid {
+    /// The region we preview, in design space: the advance, from the
+    /// descender to the ascender.
+    fn bounds(data: &EditorState) -> Rect {
+        let metrics = &data.metrics;
+        let upm = metrics.units_per_em;
+        let ascender = metrics.ascender.unwrap_or_else(|| (upm * 0.8).round());
+        let descender = metrics.descender.unwrap_or_else(|| -(upm * 0.2).round());
+        let advance = data
+            .session
+            .glyph
+            .advance
+            .as_ref()
+            .map(|a| a.width as f64)
+            .unwrap_or_else(|| (upm * 0.5).round());
+        Rect::new(0.0, descender, advance, ascender)
+    }
+
+    fn scale(&self, data: &EditorState) -> f64 {
+        self.ppem / data.metrics.units_per_em
+    }
+
+    /// Rasterize the glyph again, if it has changed since we last did.
+    fn update_coverage(&mut self, data: &EditorState) {
+        if !self.stale {
+            return;
+        }
+        self.stale = false;
+        let source = (
+            data.session.to_bezier_with_components(&data.font),
+            Self::bounds(data),
+        );
+        if self.source.as_ref() != Some(&source) {
+            let coverage = raster::rasterize(&source.0, source.1, self.scale(data));
+            self.coverage = Some(coverage);
+            self.source = Some(source);
+        }
+    }
+}
+
+impl Widget<EditorState> for PixelGrid {
+    fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut EditorState, _: &Env) {}
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &EditorState, _: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old: &EditorState, data: &EditorState, _: &Env) {
+        let session_changed = !old.session.paths.same(&data.session.paths)
+            || !old.session.components.same(&data.session.components)
+            || !old.session.glyph.same(&data.session.glyph);
+        if session_changed || !old.metrics.same(&data.metrics) {
+            self.stale = true;
+            ctx.request_layout();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &EditorState,
+        _: &Env,
+    ) -> Size {
+        // this matches the size of the grid that `raster::rasterize` fills
+        let size = Self::bounds(data).size() * self.scale(data);
+        let (width, height) = (size.width.ceil().max(0.0), size.height.ceil().max(0.0));
+        bc.constrain((width * CELL_SIZE, height * CELL_SIZE))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &EditorState, _: &Env) {
+        self.update_coverage(data);
+        let coverage = match self.coverage.as_ref() {
+            Some(coverage) => coverage,
+            None => return,
+        };
+        let frame = ctx.size().to_rect();
+        ctx.fill(frame, &Color::WHITE);
+        for row in 0..coverage.height {
+            for col in 0..coverage.width {
+                let origin = (col as f64 * CELL_SIZE, row as f64 * CELL_SIZE);
+                let cell = Rect::from_origin_size(origin, (CELL_SIZE, CELL_SIZE));
+                let value = coverage.get(col, row);
+                if value > 0.0 {
+                    ctx.fill(cell, &Color::grey(1.0 - value));
+                }
+                ctx.stroke(cell, &GRID_COLOR, 0.5);
+            }
+        }
+    }
+}
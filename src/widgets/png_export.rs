@@ -0,0 +1,55 @@
+//! A panel for choosing how to export the current glyph as a PNG.
+//!
+//! This is intended to be shown as a modal panel in the editor window.
+
+use druid::widget::{Checkbox, CrossAxisAlignment, Flex, Label};
+use druid::{EventCtx, Widget, WidgetExt};
+
+use crate::consts;
+use crate::data::EditorState;
+use crate::export::PngOptions;
+use crate::theme;
+use crate::widgets::prompt::{panel, Prompt};
+use crate::widgets::EditableLabel;
+
+pub fn png_export_prompt() -> impl Widget<EditorState> {
+    Prompt::new(PngOptions::default(), build_widget(), |ctx, options, _| {
+        submit(ctx, options)
+    })
+}
+
+fn build_widget() -> impl Widget<PngOptions> {
+    let body = Flex::column()
+        .with_child(Label::new("Export glyph as PNG:"))
+        .with_default_spacer()
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Label::new("Pixels per em:").with_text_color(theme::SECONDARY_TEXT_COLOR),
+                )
+                .with_default_spacer()
+                .with_child(
+                    EditableLabel::parse()
+                        .lens(PngOptions::size)
+                        .fix_width(60.0),
+                ),
+        )
+        .with_default_spacer()
+        .with_child(Checkbox::new("Show metrics").lens(PngOptions::show_metrics))
+        .with_default_spacer()
+        .with_child(Checkbox::new("White background").lens(PngOptions::background))
+        .cross_axis_alignment(CrossAxisAlignment::End);
+    panel(body, "Export", 200.)
+}
+
+/// If the options are usable, ask the editor to export them, returning
+/// `true` if the panel can be dismissed.
+fn submit(ctx: &mut EventCtx, options: &PngOptions) -> bool {
+    if options.size > 0.0 {
+        ctx.submit_command(consts::cmd::EXPORT_PNG.with(options.clone()));
+        true
+    } else {
+        log::warn!("invalid export size {}", options.size);
+        false
+    }
+}
@@ -0,0 +1,205 @@
+//! Modal panels that ask for some input before doing something.
+//!
+//! These are intended to be shown with `ModalHost` in the editor window.
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, TextBox};
+use druid::{Color, HotKey, KbKey, Lens, Selector, WidgetExt, WidgetPod};
+
+use crate::data::EditorState;
+use crate::theme;
+use crate::widgets::ModalHost;
+
+/// Sent from a panel's confirm button to the prompt, which knows the
+/// editor state.
+const SUBMIT: Selector = Selector::new("runebender.prompt-submit");
+
+type SubmitFn<S> = dyn Fn(&mut EventCtx, &S, &EditorState) -> bool;
+type ChangedFn<S> = dyn Fn(&mut S, &EditorState);
+
+/// A widget that owns the input being edited, so that it does not need to be
+/// part of the editor state.
+pub struct Prompt<S> {
+    state: S,
+    inner: WidgetPod<S, Box<dyn Widget<S>>>,
+    submit: Box<SubmitFn<S>>,
+    changed: Option<Box<ChangedFn<S>>>,
+}
+
+/// The state of a text prompt: the text being typed, and why it can't be used.
+#[derive(Debug, Clone, Default, Data, Lens)]
+pub struct TextState {
+    text: String,
+    error: String,
+}
+
+impl<S: Data> Prompt<S> {
+    /// Create a prompt for `state`, shown with `widget`.
+    ///
+    /// `submit` is called when the confirm button or Enter is pressed; if it
+    /// returns `true` the prompt is dismissed.
+    pub fn new(
+        state: S,
+        widget: impl Widget<S> + 'static,
+        submit: impl Fn(&mut EventCtx, &S, &EditorState) -> bool + 'static,
+    ) -> Self {
+        Prompt {
+            state,
+            inner: WidgetPod::new(widget.boxed()),
+            submit: Box::new(submit),
+            changed: None,
+        }
+    }
+
+    /// Call `f` whenever the state is changed by the panel.
+    pub fn on_change(mut self, f: impl Fn(&mut S, &EditorState) + 'static) -> Self {
+        self.changed = Some(Box::new(f));
+        self
+    }
+
+    fn submit(&mut self, ctx: &mut EventCtx, data: &EditorState) {
+        if (self.submit)(ctx, &self.state, data) {
+            ctx.submit_command(ModalHost::DISMISS_MODAL);
+        }
+    }
+}
+
+/// Lay out a prompt's `body` above a row with a cancel button and a confirm
+/// button titled `confirm`.
+pub fn panel<S: Data>(
+    body: impl Widget<S> + 'static,
+    confirm: &str,
+    height: f64,
+) -> impl Widget<S> {
+    Flex::column()
+        .with_child(body)
+        .with_flex_spacer(1.0)
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Button::new("Cancel")
+                        .on_click(|ctx, _, _| ctx.submit_command(ModalHost::DISMISS_MODAL)),
+                )
+                .with_default_spacer()
+                .with_child(Button::new(confirm).on_click(|ctx, _, _| ctx.submit_command(SUBMIT))),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::End)
+        .fix_height(height)
+        .padding(16.0)
+        .background(Color::WHITE)
+}
+
+/// A prompt for a line of text.
+///
+/// `validate` turns the text into a value, or explains why it can't be used;
+/// the explanation is shown under the text box. When the text is confirmed
+/// the value is passed to `submit`.
+pub fn text_prompt<T: 'static>(
+    label: &str,
+    placeholder: &str,
+    confirm: &str,
+    text: String,
+    validate: impl Fn(&str, &EditorState) -> Result<T, String> + 'static,
+    submit: impl Fn(&mut EventCtx, T) + 'static,
+) -> impl Widget<EditorState> {
+    let body = Flex::column()
+        .with_child(Label::new(label))
+        .with_default_spacer()
+        .with_child(
+            TextBox::new()
+                .with_placeholder(placeholder)
+                .fix_width(200.0)
+                .lens(TextState::text),
+        )
+        .with_child(
+            Label::dynamic(|error: &String, _| error.clone())
+                .with_text_color(theme::SECONDARY_TEXT_COLOR)
+                .with_font(theme::UI_DETAIL_FONT)
+                .lens(TextState::error),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::End);
+
+    let validate = std::rc::Rc::new(validate);
+    let validate_on_change = validate.clone();
+    let state = TextState {
+        text,
+        error: String::new(),
+    };
+    Prompt::new(
+        state,
+        panel(body, confirm, 140.),
+        move |ctx, state, data| match validate(state.text.trim(), data) {
+            Ok(value) => {
+                submit(ctx, value);
+                true
+            }
+            Err(e) => {
+                log::warn!("invalid input '{}': {}", state.text, e);
+                false
+            }
+        },
+    )
+    .on_change(move |state, data| {
+        state.error = if state.text.trim().is_empty() {
+            String::new()
+        } else {
+            validate_on_change(state.text.trim(), data)
+                .err()
+                .unwrap_or_default()
+        };
+    })
+}
+
+impl<S: Data> Widget<EditorState> for Prompt<S> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut EditorState, env: &Env) {
+        match event {
+            Event::KeyDown(k) if HotKey::new(None, KbKey::Enter).matches(k) => {
+                self.submit(ctx, data);
+                ctx.set_handled();
+            }
+            Event::KeyDown(k) if HotKey::new(None, KbKey::Escape).matches(k) => {
+                ctx.submit_command(ModalHost::DISMISS_MODAL);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(SUBMIT) => {
+                self.submit(ctx, data);
+                ctx.set_handled();
+            }
+            _ => {
+                let pre_state = self.state.clone();
+                self.inner.event(ctx, event, &mut self.state, env);
+                if !pre_state.same(&self.state) {
+                    if let Some(changed) = &self.changed {
+                        changed(&mut self.state, data);
+                    }
+                    ctx.request_update();
+                }
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _: &EditorState, env: &Env) {
+        self.inner.lifecycle(ctx, event, &self.state, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &EditorState, _: &EditorState, env: &Env) {
+        self.inner.update(ctx, &self.state, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _: &EditorState,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, &self.state, env);
+        self.inner
+            .set_layout_rect(ctx, &self.state, env, size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _: &EditorState, env: &Env) {
+        self.inner.paint(ctx, &self.state, env);
+    }
+}
@@ -161,6 +161,12 @@ impl<T: Widget<EditorState>> Widget<EditorState> for ScrollZoom<T> {
                 self.after_zoom_changed(ctx, env);
                 return;
             }
+            Event::Command(c) if c.is(cmd::PAN_CANVAS) => {
+                self.child.scroll_by(*c.get_unchecked(cmd::PAN_CANVAS));
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
             Event::WindowSize(size) if self.needs_center_after_layout => {
                 self.set_initial_viewport(data, *size);
                 ctx.request_layout();
@@ -249,8 +249,14 @@ impl Default for Toolbar {
             hotkey: HotKey::new(None, "m"),
         };
 
+        let transform = ToolbarItem {
+            name: "Transform",
+            icon: constrain_path(transform_path()),
+            hotkey: HotKey::new(None, "t"),
+        };
+
         Toolbar::new(vec![
-            select, pen, knife, preview, measure, rectangle, ellipse,
+            select, transform, pen, knife, preview, measure, rectangle, ellipse,
         ])
     }
 }
@@ -382,6 +388,25 @@ fn knife_path() -> BezPath {
     bez
 }
 
+fn transform_path() -> BezPath {
+    let mut bez = BezPath::new();
+
+    bez.move_to((40.0, 40.0));
+    bez.line_to((260.0, 40.0));
+    bez.line_to((260.0, 260.0));
+    bez.line_to((40.0, 260.0));
+    bez.close_path();
+
+    for &(x, y) in &[(0.0, 0.0), (220.0, 0.0), (220.0, 220.0), (0.0, 220.0)] {
+        bez.move_to((x, y));
+        bez.line_to((x + 80.0, y));
+        bez.line_to((x + 80.0, y + 80.0));
+        bez.line_to((x, y + 80.0));
+        bez.close_path();
+    }
+    bez
+}
+
 fn ellipse_path() -> BezPath {
     let mut bez = BezPath::new();
 